@@ -1,89 +1,102 @@
+use argh::FromArgs;
 use printpdf::*;
+use std::path::PathBuf;
+use weekly::cells::{CellAlignment, CellRenderer, Checkbox};
+use weekly::patterns::{Pattern, RuleHeight, RuledPaper};
 use weekly::{
-    save_one_page_document, Attributes, GridDescription, HasRenderAttrs, Instructions, NumericUnit,
-    TGrid, Unit, WRect,
+    save_document, Attributes, FormField, Instructions, Layout, Margins, NumericUnit, PageSetup,
+    Unit, WRect,
 };
 
-struct ActiveDescription {
-    bounds: WRect,
-    task_height: Unit,
-}
+#[derive(FromArgs)]
+/// Prints a simple task list: two columns of lines with a checkbox on each.
+struct Args {
+    /// add checkbox and text fields that can be filled in in a PDF viewer
+    #[argh(switch)]
+    form: bool,
 
-impl ActiveDescription {
-    pub fn with_bounds(bounds: WRect, task_height: Unit) -> ActiveDescription {
-        ActiveDescription {
-            bounds,
-            task_height,
-        }
-    }
+    /// output filename, or - for stdout (default: task-list.pdf)
+    #[argh(option, short = 'o', default = "PathBuf::from(\"task-list.pdf\")")]
+    output: PathBuf,
 }
 
-impl GridDescription for ActiveDescription {
-    fn bounds(&self) -> WRect {
-        self.bounds.clone()
-    }
-
-    fn num_cols(&self) -> Option<usize> {
-        Some(1)
-    }
-
-    fn row_height(&self) -> Option<Unit> {
-        Some(self.task_height)
-    }
-
-    fn horiz_line_style(&self, _index: usize, _num_rows: usize) -> Option<Attributes> {
-        Some(Attributes::default().with_stroke_width(0.0).with_dash(3, 2))
-    }
-
-    fn vert_line_style(&self, _index: usize, _num_cols: usize) -> Option<Attributes> {
-        None
-    }
-
-    fn render_cell_contents(
-        &self,
-        _row: usize,
-        _col: usize,
-        cell_rect: &WRect,
-        instructions: &mut Instructions,
-    ) {
-        let check_rect = WRect::with_dimensions(self.task_height / 2, self.task_height / 2)
-            .move_to(
-                cell_rect.left() + self.task_height / 4,
-                cell_rect.top() - self.task_height / 4,
-            );
-        instructions.push_shape(check_rect.stroke());
+// A column of dashed lines with a checkbox on each one. With a `field_prefix`, each line gets a
+// checkbox field and a text field, named like "Left 3" and "Left 3 task".
+fn render_tasks(
+    rect: &WRect,
+    task_height: Unit,
+    field_prefix: Option<&str>,
+    instructions: &mut Instructions,
+) {
+    let ruled = RuledPaper::new(RuleHeight::Custom(task_height))
+        .with_first_line(Unit::zero())
+        .with_line_attributes(Attributes::default().with_stroke_width(0.0).with_dash(3, 2));
+    ruled.render(rect, instructions);
+
+    let checkbox = Checkbox::new(task_height / 2).with_alignment(CellAlignment::Left);
+    for (index, line_rect) in ruled.line_rects(rect).iter().enumerate() {
+        match field_prefix {
+            Some(prefix) => {
+                let name = format!("{} {}", prefix, index + 1);
+                checkbox
+                    .clone()
+                    .with_field(name.as_str())
+                    .render(line_rect, instructions);
+                // The text starts where the checkbox's inset would put a second box.
+                let text_rect =
+                    line_rect.inset_all_q1(task_height, Unit::zero(), Unit::zero(), Unit::zero());
+                instructions.field(&text_rect, FormField::text(format!("{} task", name)));
+            }
+            None => checkbox.render(line_rect, instructions),
+        }
     }
 }
 
-fn render_active(_: &PdfDocumentReference, page_bounds: &WRect) -> weekly::Result<Instructions> {
-    let half_page = page_bounds
-        .resize(page_bounds.width() / 2, page_bounds.height())
-        // A rounding error prevents rendering the last line,
-        // so we add a smidge of extra vertical space.
-        .inset_all_q1(Unit::zero(), Unit::zero(), Unit::zero(), (-0.1).inches());
-    let left_bounds =
-        half_page.inset_all_q1(0.25.inches(), 0.25.inches(), 0.125.inches(), 0.25.inches());
-    let right_bounds = left_bounds.move_to(half_page.right() + 0.125.inches(), left_bounds.top());
+fn render_active(
+    form: bool,
+    _: &PdfDocumentReference,
+    page_setup: &PageSetup,
+) -> weekly::Result<Vec<Instructions>> {
+    let content_bounds = page_setup.content_rect(1);
+    let halves = Layout::columns()
+        .with_gap(0.25.inches())
+        .flex("left", 1.0)
+        .flex("right", 1.0)
+        .split(&content_bounds);
 
     let mut instructions = Instructions::default();
 
     let task_height = 0.25.inches();
 
-    let description = ActiveDescription::with_bounds(left_bounds, task_height);
-    let grid = TGrid::with_description(description);
-    grid.append_to_instructions(&mut instructions);
-
-    let description = ActiveDescription::with_bounds(right_bounds, task_height);
-    let grid = TGrid::with_description(description);
-    grid.append_to_instructions(&mut instructions);
-
-    Ok(instructions)
+    render_tasks(
+        &halves["left"],
+        task_height,
+        form.then_some("Left"),
+        &mut instructions,
+    );
+    render_tasks(
+        &halves["right"],
+        task_height,
+        form.then_some("Right"),
+        &mut instructions,
+    );
+
+    Ok(vec![instructions])
 }
 
 fn main() -> weekly::Result<()> {
+    let args: Args = argh::from_env();
     let doc_title = "Simple task list";
-    let output_filename = "task-list.pdf";
-    let page_bounds = weekly::sizes::letter();
-
-    save_one_page_document(doc_title, output_filename, &page_bounds, render_active)
+    // A rounding error prevents rendering the last line,
+    // so we add a smidge of extra vertical space.
+    let page_setup = PageSetup::new(&weekly::sizes::letter()).with_margins(Margins::new(
+        0.25.inches(),
+        0.25.inches(),
+        0.25.inches(),
+        0.15.inches(),
+    ));
+
+    save_document(doc_title, &args.output, &page_setup, |doc, page_setup| {
+        render_active(args.form, doc, page_setup)
+    })
 }
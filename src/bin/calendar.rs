@@ -0,0 +1,518 @@
+use argh::FromArgs;
+use chrono::{Datelike, NaiveDate, Weekday};
+use printpdf::Color;
+use std::borrow::Cow;
+use std::path::PathBuf;
+use weekly::cells::{CellAlignment, CellRenderer};
+use weekly::patterns::{Pattern, RuleHeight, RuledPaper};
+use weekly::{
+    save_document, sizes, Attributes, Colors, DateSpan, Datetools, FontProxy, GridDescription,
+    Instructions, LabelOrientation, Layout, LinkTarget, Margins, MonthCalendar, NumericUnit,
+    PageSetup, TGrid, Tabs, Unit, WRect, WeeklyError,
+};
+
+#[derive(FromArgs)]
+/// Prints calendars.
+struct Args {
+    /// output filename, or - for stdout
+    #[argh(option, short = 'o')]
+    output: Option<PathBuf>,
+
+    /// the day that weeks start on (default: sunday)
+    #[argh(option, default = "Weekday::Sun", from_str_fn(parse_weekday))]
+    first_day: Weekday,
+
+    #[argh(subcommand)]
+    command: CalendarCommand,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum CalendarCommand {
+    Month(MonthArgs),
+    Quarter(QuarterArgs),
+    Year(YearArgs),
+    Planner(PlannerArgs),
+    Digital(DigitalArgs),
+}
+
+#[derive(FromArgs)]
+/// A full page calendar for each month.
+#[argh(subcommand, name = "month")]
+struct MonthArgs {
+    /// the months to print, like 2026-10, next-month or 2026-01..2026-06 (default: this month)
+    #[argh(option)]
+    start: Option<DateSpan>,
+
+    /// number of months, one per page (default: the months in --start)
+    #[argh(option)]
+    months: Option<usize>,
+}
+
+#[derive(FromArgs)]
+/// The three months of a quarter on one page.
+#[argh(subcommand, name = "quarter")]
+struct QuarterArgs {
+    /// the quarters to print, like 2026-Q4, next-quarter or 2026 (default: this quarter)
+    #[argh(option)]
+    start: Option<DateSpan>,
+
+    /// number of quarters, one per page (default: the quarters in --start)
+    #[argh(option)]
+    quarters: Option<usize>,
+}
+
+#[derive(FromArgs)]
+/// All twelve months of a year on one page.
+#[argh(subcommand, name = "year")]
+struct YearArgs {
+    /// the year (default: this year)
+    #[argh(positional)]
+    year: Option<i32>,
+}
+
+#[derive(FromArgs)]
+/// A year planner, with a row for each month and a column for each day.
+#[argh(subcommand, name = "planner")]
+struct PlannerArgs {
+    /// the year (default: this year)
+    #[argh(positional)]
+    year: Option<i32>,
+}
+
+#[derive(FromArgs)]
+/// A linked calendar for tablets: a year page, a page for each month and a page for each day,
+/// with tabs down the side to get around.
+#[argh(subcommand, name = "digital")]
+struct DigitalArgs {
+    /// the year (default: this year)
+    #[argh(positional)]
+    year: Option<i32>,
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday, String> {
+    value
+        .parse()
+        .map_err(|_| format!("unknown day of the week: {}", value))
+}
+
+// Years are kept to 1-9999, like date arguments, so that the months after them can be found.
+fn year_start(year: Option<i32>) -> weekly::Result<NaiveDate> {
+    match year {
+        Some(year) => NaiveDate::from_ymd_opt(year, 1, 1)
+            .filter(|_| (1..=9999).contains(&year))
+            .ok_or_else(|| {
+                WeeklyError::BadDate(format!("{} isn't between the years 1 and 9999", year))
+            }),
+        None => Ok(weekly::today().first_of_year()),
+    }
+}
+
+fn months_from(start: &NaiveDate, n: usize) -> Vec<NaiveDate> {
+    let mut month = start.first_of_month();
+    let mut months = vec![];
+    for _ in 0..n {
+        months.push(month);
+        month = month.next_month();
+    }
+    months
+}
+
+// Draws the title centered at the top of `rect`, and returns the rest of the rect.
+fn render_title(title: &str, rect: &WRect, instructions: &mut Instructions) -> WRect {
+    let font = FontProxy::helvetica_bold();
+    let font_size = 24.0;
+    let x = rect.left() + (rect.width() - font.text_width(title, font_size)) / 2;
+    let y = rect.top() - font.cap_height(font_size);
+    instructions.push_text(title, font_size, x, y, font);
+
+    rect.inset_all_q1(Unit::zero(), 0.625.inches(), Unit::zero(), Unit::zero())
+}
+
+fn render_month_pages(
+    args: &MonthArgs,
+    first_day: Weekday,
+    page_setup: &PageSetup,
+) -> Vec<Instructions> {
+    let span = args
+        .start
+        .unwrap_or_else(|| DateSpan::month(&weekly::today()));
+    let num_months = args.months.unwrap_or_else(|| span.months().len());
+    months_from(&span.first(), num_months)
+        .iter()
+        .enumerate()
+        .map(|(index, month)| {
+            let mut instructions = Instructions::default();
+            instructions.bookmark(&month_bookmark(month));
+            MonthCalendar::new(month)
+                .with_first_weekday(first_day)
+                .render(&page_setup.content_rect(index + 1), &mut instructions);
+            instructions
+        })
+        .collect()
+}
+
+// A page of small month calendars, `num_cols` across, under a title.
+// With `links`, each calendar links to the page for its month.
+fn render_months_page(
+    title: &str,
+    months: &[NaiveDate],
+    num_cols: usize,
+    first_day: Weekday,
+    links: bool,
+    rect: &WRect,
+) -> Instructions {
+    let mut instructions = Instructions::default();
+    let grid_rect = render_title(title, rect, &mut instructions);
+    TGrid::with_description(MonthsDescription {
+        bounds: grid_rect,
+        months: months.to_vec(),
+        num_cols,
+        first_day,
+        links,
+    })
+    .append_to_instructions(&mut instructions);
+    instructions
+}
+
+struct MonthsDescription {
+    bounds: WRect,
+    months: Vec<NaiveDate>,
+    num_cols: usize,
+    first_day: Weekday,
+    links: bool,
+}
+
+impl GridDescription for MonthsDescription {
+    fn bounds(&self) -> WRect {
+        self.bounds.clone()
+    }
+
+    fn num_rows(&self) -> Option<usize> {
+        Some(self.months.len().div_ceil(self.num_cols))
+    }
+
+    fn num_cols(&self) -> Option<usize> {
+        Some(self.num_cols)
+    }
+
+    fn horiz_line_style(&self, _index: usize, _num_rows: usize) -> Option<Attributes> {
+        None
+    }
+
+    fn vert_line_style(&self, _index: usize, _num_cols: usize) -> Option<Attributes> {
+        None
+    }
+
+    fn cell_link(&self, row: usize, col: usize) -> Option<LinkTarget> {
+        let month = self.months.get(row * self.num_cols + col)?;
+        if self.links {
+            Some(LinkTarget::anchor(month_anchor(month)))
+        } else {
+            None
+        }
+    }
+
+    fn render_cell_contents(
+        &self,
+        row: usize,
+        col: usize,
+        cell_rect: &WRect,
+        instructions: &mut Instructions,
+    ) {
+        if let Some(month) = self.months.get(row * self.num_cols + col) {
+            let padding = 0.125.inches();
+            MonthCalendar::new(month)
+                .with_title(month.format("%B").to_string())
+                .with_first_weekday(self.first_day)
+                .with_six_weeks(true)
+                .with_adjacent_days(false)
+                .with_day_numbers(CellAlignment::Center, 45.0)
+                .with_line_attributes(None)
+                .render(
+                    &cell_rect.inset_all_q1(padding, padding, padding, padding),
+                    instructions,
+                );
+        }
+    }
+}
+
+// Months down the side, and the days of the month across the top.
+struct PlannerDescription {
+    bounds: WRect,
+    months: Vec<NaiveDate>,
+}
+
+impl PlannerDescription {
+    // The date in a cell, or None if the month is too short to have that day.
+    fn date(&self, row: usize, col: usize) -> Option<NaiveDate> {
+        let month = self.months[row];
+        NaiveDate::from_ymd_opt(month.year(), month.month(), col as u32 + 1)
+    }
+}
+
+impl GridDescription for PlannerDescription {
+    fn bounds(&self) -> WRect {
+        self.bounds.clone()
+    }
+
+    fn num_rows(&self) -> Option<usize> {
+        Some(self.months.len())
+    }
+
+    fn num_cols(&self) -> Option<usize> {
+        Some(31)
+    }
+
+    fn row_label_width(&self) -> Option<Unit> {
+        Some(0.875.inches())
+    }
+
+    fn col_label_height(&self) -> Option<Unit> {
+        Some(0.25.inches())
+    }
+
+    fn col_label_orientation(&self) -> LabelOrientation {
+        LabelOrientation::Horizontal
+    }
+
+    fn row_label(&self, index: usize) -> Cow<'_, str> {
+        self.months[index].format("%b").to_string().into()
+    }
+
+    fn col_label(&self, index: usize) -> Cow<'_, str> {
+        (index + 1).to_string().into()
+    }
+
+    fn horiz_line_style(&self, _index: usize, _num_rows: usize) -> Option<Attributes> {
+        Some(Attributes::default().with_stroke_width(0.5))
+    }
+
+    fn vert_line_style(&self, _index: usize, _num_cols: usize) -> Option<Attributes> {
+        Some(
+            Attributes::default()
+                .with_stroke_width(0.0)
+                .with_stroke_color(&Colors::gray(0.5)),
+        )
+    }
+
+    fn cell_background(&self, row: usize, col: usize) -> Option<Color> {
+        match self.date(row, col) {
+            None => Some(Colors::gray(0.6)),
+            Some(date) if date.is_weekend() => Some(Colors::gray(0.9)),
+            Some(_) => None,
+        }
+    }
+
+    fn font(&self) -> FontProxy {
+        FontProxy::helvetica_bold()
+    }
+}
+
+// The outline has a bookmark for each year, with the months under it.
+fn month_bookmark(date: &NaiveDate) -> [String; 2] {
+    [date.format("%Y").to_string(), date.format("%B").to_string()]
+}
+
+// Anchors for the pages of the digital calendar.
+fn year_anchor(date: &NaiveDate) -> String {
+    date.format("%Y").to_string()
+}
+
+fn month_anchor(date: &NaiveDate) -> String {
+    date.format("%Y-%m").to_string()
+}
+
+fn day_anchor(date: &NaiveDate) -> String {
+    // This is the anchor that MonthCalendar::with_day_links() uses.
+    date.format("%Y-%m-%d").to_string()
+}
+
+// Splits a page of the digital calendar into the page contents and the tabs down the right.
+fn render_digital_page(
+    rect: &WRect,
+    start: &NaiveDate,
+    selected: Option<usize>,
+    f: impl FnOnce(&WRect, &mut Instructions),
+) -> Instructions {
+    let split = Layout::columns()
+        .with_gap(0.125.inches())
+        .flex("page", 1.0)
+        .fixed("tabs", 0.375.inches())
+        .split(rect);
+
+    let mut tabs = Tabs::default()
+        .with_tab(year_anchor(start), LinkTarget::anchor(year_anchor(start)))
+        .with_selected(selected);
+    for month in months_from(start, 12) {
+        tabs = tabs.with_tab(
+            month.format("%b").to_string(),
+            LinkTarget::anchor(month_anchor(&month)),
+        );
+    }
+
+    let mut instructions = Instructions::default();
+    f(&split["page"], &mut instructions);
+    tabs.render(&split["tabs"], &mut instructions);
+    instructions
+}
+
+fn render_digital(
+    start: &NaiveDate,
+    first_day: Weekday,
+    page_setup: &PageSetup,
+) -> Vec<Instructions> {
+    let mut pages = vec![];
+
+    let rect = page_setup.content_rect(1);
+    pages.push(render_digital_page(
+        &rect,
+        start,
+        Some(0),
+        |rect, instructions| {
+            instructions.anchor(year_anchor(start));
+            instructions.bookmark(&[year_anchor(start)]);
+            let months = months_from(start, 12);
+            instructions.append(render_months_page(
+                &year_anchor(start),
+                &months,
+                3,
+                first_day,
+                true,
+                rect,
+            ));
+        },
+    ));
+
+    for (index, month) in months_from(start, 12).iter().enumerate() {
+        let rect = page_setup.content_rect(pages.len() + 1);
+        pages.push(render_digital_page(
+            &rect,
+            start,
+            Some(index + 1),
+            |rect, instructions| {
+                instructions.anchor(month_anchor(month));
+                instructions.bookmark(&month_bookmark(month));
+                MonthCalendar::new(month)
+                    .with_first_weekday(first_day)
+                    .with_day_links(true)
+                    .render(rect, instructions);
+            },
+        ));
+    }
+
+    let num_days = NaiveDate::from_ymd(start.year() + 1, 1, 1)
+        .signed_duration_since(*start)
+        .num_days();
+    for date in start.date_range(num_days) {
+        let rect = page_setup.content_rect(pages.len() + 1);
+        let selected = Some(date.month0() as usize + 1);
+        pages.push(render_digital_page(
+            &rect,
+            start,
+            selected,
+            |rect, instructions| {
+                instructions.anchor(day_anchor(&date));
+                let mut bookmark = month_bookmark(&date).to_vec();
+                bookmark.push(date.format("%A %-d").to_string());
+                instructions.bookmark(&bookmark);
+                let title = date.format("%A, %B %-d, %Y").to_string();
+                let notes_rect = render_title(&title, rect, instructions);
+                RuledPaper::new(RuleHeight::College)
+                    .with_line_attributes(
+                        Attributes::default()
+                            .with_stroke_width(0.0)
+                            .with_stroke_color(&Colors::gray(0.7)),
+                    )
+                    .render(&notes_rect, instructions);
+            },
+        ));
+    }
+    pages
+}
+
+fn main() -> weekly::Result<()> {
+    let args: Args = argh::from_env();
+
+    let margins = Margins::all(0.5.inches());
+    let portrait = PageSetup::new(&sizes::letter()).with_margins(margins);
+    let landscape = PageSetup::new(&sizes::landscape(&sizes::letter())).with_margins(margins);
+    let tablet = PageSetup::new(&sizes::remarkable2()).with_margins(Margins::all(0.25.inches()));
+    let output = |default: String| args.output.clone().unwrap_or_else(|| default.into());
+    let first_day = args.first_day;
+
+    match &args.command {
+        CalendarCommand::Month(month_args) => {
+            let start = month_args
+                .start
+                .map_or_else(weekly::today, |span| span.first());
+            let output = output(format!("calendar-{}.pdf", start.format("%Y-%m")));
+            save_document("Monthly calendar", output, &portrait, |_, page_setup| {
+                Ok(render_month_pages(month_args, first_day, page_setup))
+            })
+        }
+        CalendarCommand::Quarter(quarter_args) => {
+            let span = quarter_args
+                .start
+                .unwrap_or_else(|| DateSpan::quarter(&weekly::today()));
+            let start = span.first().first_of_quarter();
+            let num_quarters = quarter_args
+                .quarters
+                .unwrap_or_else(|| DateSpan::new(start, span.last()).months().len().div_ceil(3));
+            let output = output(format!("quarter-{}.pdf", start.format("%Y-%m")));
+            save_document("Quarterly calendar", output, &portrait, |_, page_setup| {
+                let months = months_from(&start, num_quarters * 3);
+                Ok(months
+                    .chunks(3)
+                    .enumerate()
+                    .map(|(index, quarter)| {
+                        let title = format!("Q{} {}", quarter[0].quarter(), quarter[0].year());
+                        let rect = page_setup.content_rect(index + 1);
+                        let mut instructions =
+                            render_months_page(&title, quarter, 1, first_day, false, &rect);
+                        instructions.bookmark(&[
+                            quarter[0].format("%Y").to_string(),
+                            format!("Q{}", quarter[0].quarter()),
+                        ]);
+                        instructions
+                    })
+                    .collect())
+            })
+        }
+        CalendarCommand::Year(year_args) => {
+            let start = year_start(year_args.year)?;
+            let title = start.year().to_string();
+            let output = output(format!("year-{}.pdf", title));
+            save_document("Year calendar", output, &portrait, |_, page_setup| {
+                let months = months_from(&start, 12);
+                let rect = page_setup.content_rect(1);
+                Ok(vec![render_months_page(
+                    &title, &months, 3, first_day, false, &rect,
+                )])
+            })
+        }
+        CalendarCommand::Planner(planner_args) => {
+            let start = year_start(planner_args.year)?;
+            let title = start.year().to_string();
+            let output = output(format!("planner-{}.pdf", title));
+            save_document("Year planner", output, &landscape, |_, page_setup| {
+                let mut instructions = Instructions::default();
+                let grid_rect =
+                    render_title(&title, &page_setup.content_rect(1), &mut instructions);
+                TGrid::with_description(PlannerDescription {
+                    bounds: grid_rect,
+                    months: months_from(&start, 12),
+                })
+                .append_to_instructions(&mut instructions);
+                Ok(vec![instructions])
+            })
+        }
+        CalendarCommand::Digital(digital_args) => {
+            let start = year_start(digital_args.year)?;
+            let output = output(format!("digital-{}.pdf", year_anchor(&start)));
+            save_document("Digital calendar", output, &tablet, |_, page_setup| {
+                Ok(render_digital(&start, first_day, page_setup))
+            })
+        }
+    }
+}
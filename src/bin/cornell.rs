@@ -1,64 +1,32 @@
+use argh::FromArgs;
 use printpdf::PdfDocumentReference;
+use std::path::PathBuf;
+use weekly::patterns::{Pattern, RuleHeight, RuledPaper};
 use weekly::{
-    save_one_page_document, Attributes, Colors, GridDescription, HasRenderAttrs, Instructions,
-    TGrid, Unit, WLine, WRect,
+    save_one_page_document, Attributes, Colors, Frame, HasRenderAttrs, Instructions, Unit, WRect,
 };
 
-const NOTE_HORIZ_PCT: f64 = 70.0;
-const NOTE_VERT_PCT: f64 = 82.0;
-
-struct CornellDescription {
-    bounds: WRect,
-}
-
-impl CornellDescription {
-    pub fn with_bounds(bounds: WRect) -> CornellDescription {
-        CornellDescription { bounds }
-    }
+#[derive(FromArgs)]
+/// Prints a Cornell notes page for the Remarkable 2.
+struct Args {
+    /// output filename, or - for stdout (default: cornell.pdf)
+    #[argh(option, short = 'o', default = "PathBuf::from(\"cornell.pdf\")")]
+    output: PathBuf,
 }
 
-impl GridDescription for CornellDescription {
-    fn bounds(&self) -> WRect {
-        self.bounds.clone()
-    }
-
-    fn num_cols(&self) -> Option<usize> {
-        Some(1)
-    }
-
-    fn row_height(&self) -> Option<Unit> {
-        Some(weekly::sizes::cornell_rule_height())
-    }
-
-    fn horiz_line_style(&self, index: usize, num_rows: usize) -> Option<Attributes> {
-        if index == num_rows {
-            // Don't render the last line because it coincides with the horizontal rule at the
-            // top of the notes area.
-            None
-        } else {
-            Some(
-                Attributes::default()
-                    .with_stroke_width(0.0)
-                    .with_stroke_color(&Colors::gray(0.8)),
-            )
-        }
-    }
-
-    fn vert_line_style(&self, _index: usize, _num_cols: usize) -> Option<Attributes> {
-        None
-    }
-}
+const NOTE_HORIZ_PCT: f64 = 70.0;
+const NOTE_VERT_PCT: f64 = 82.0;
 
-fn compute_bottom_line_y(device_rect: &WRect) -> Unit {
-    let cornell_height = device_rect.height().pct(NOTE_VERT_PCT);
-    let rule_height = weekly::sizes::cornell_rule_height();
+// Distance from the top of the page to the bottom of the notes area.
+fn compute_bottom_line_y(frame: &Frame) -> Unit {
+    let cornell_height = frame.height().pct(NOTE_VERT_PCT);
+    let rule_height = RuleHeight::College.height();
     let lines = cornell_height / rule_height;
-    device_rect.height()
-        - if rule_height * lines != cornell_height {
-            rule_height * (lines + 1.0)
-        } else {
-            rule_height
-        }
+    if rule_height * lines != cornell_height {
+        rule_height * (lines + 1.0)
+    } else {
+        rule_height
+    }
 }
 
 fn render_cornell(_: &PdfDocumentReference, device_rect: &WRect) -> weekly::Result<Instructions> {
@@ -67,37 +35,40 @@ fn render_cornell(_: &PdfDocumentReference, device_rect: &WRect) -> weekly::Resu
     instructions.set_stroke_width(0.75);
     instructions.set_stroke_color(Colors::gray(0.6));
 
-    let bottom_line_y = compute_bottom_line_y(device_rect);
+    let frame = Frame::new(device_rect);
+    let bottom_line_y = compute_bottom_line_y(&frame);
+    let left_line_x = frame.width().pct(100.0 - NOTE_HORIZ_PCT);
 
-    let notes_bottom_line = WLine::line(
-        device_rect.left(),
-        bottom_line_y,
-        device_rect.right(),
+    // The rules go down first, so that the heavier lines around the notes area cover the ones
+    // they coincide with.
+    let notes_rect = frame.rect(
+        left_line_x,
+        Unit::zero(),
+        frame.width() - left_line_x,
         bottom_line_y,
     );
+    RuledPaper::new(RuleHeight::College)
+        .with_first_line(Unit::zero())
+        .with_line_attributes(
+            Attributes::default()
+                .with_stroke_width(0.0)
+                .with_stroke_color(&Colors::gray(0.8)),
+        )
+        .render(&notes_rect, &mut instructions);
+
+    let notes_bottom_line = frame.line(Unit::zero(), bottom_line_y, frame.width(), bottom_line_y);
     instructions.push_shape(notes_bottom_line.stroke());
 
-    let left_line_x = device_rect.width().pct(100.0 - NOTE_HORIZ_PCT);
-
-    let notes_left_line = WLine::line(left_line_x, bottom_line_y, left_line_x, device_rect.top());
+    let notes_left_line = frame.line(left_line_x, Unit::zero(), left_line_x, bottom_line_y);
     instructions.push_shape(notes_left_line.stroke());
 
-    let grid_rect = WRect::with_dimensions(
-        device_rect.right() - left_line_x,
-        device_rect.top() - bottom_line_y,
-    )
-    .move_to(left_line_x, device_rect.top());
-
-    TGrid::with_description(CornellDescription::with_bounds(grid_rect))
-        .append_to_instructions(&mut instructions);
-
     Ok(instructions)
 }
 
 pub fn main() -> weekly::Result<()> {
+    let args: Args = argh::from_env();
     let doc_title = "Cornell note page";
-    let output_filename = "cornell.pdf";
     let device_rect = weekly::sizes::remarkable2();
 
-    save_one_page_document(doc_title, output_filename, &device_rect, render_cornell)
+    save_one_page_document(doc_title, args.output, &device_rect, render_cornell)
 }
@@ -3,33 +3,56 @@ use chrono::{Datelike, NaiveDate, Weekday};
 use printpdf::{Color, PdfDocumentReference};
 use std::borrow::Cow;
 use std::path::PathBuf;
+use weekly::cells::{CellRenderer, Checkbox};
 use weekly::{
-    save_one_page_document, sizes, Attributes, Colors, Datetools, HasRenderAttrs, NumericUnit,
-    Result, TGrid, Unit, WRect,
+    save_document, sizes, Attributes, Colors, DateSpan, FormField, HasRenderAttrs,
+    LabelOrientation, Margins, NumericUnit, PageSetup, Result, TGrid, Unit, WRect, WeeklyError,
 };
-use weekly::{GridDescription, Instructions};
+use weekly::{watch_files, GridDescription, Instructions};
 
 #[derive(Debug, FromArgs)]
 /// Generates a daily checklist for every date supplied.
 struct Args {
-    /// month for which to generate the checklist
+    /// months for which to generate checklists, like 2026-10, next-month or 2026-Q4, or other
+    /// dates, like 2026-10-05..2026-10-25, for a checklist with just those days. A single day
+    /// means its month.
     #[argh(positional)]
-    dates: Vec<NaiveDate>,
+    dates: Vec<DateSpan>,
 
     // TODO: try to unify args across apps.
-    // TODO: these arguments are kind of ugly. Fix them.
-    /// optional start date
+    /// optional start date, used with --end-date for a single checklist
     #[argh(option, short = 's')]
-    start_date: Option<NaiveDate>,
+    start_date: Option<DateSpan>,
 
     /// optional end date
     #[argh(option, short = 'e')]
-    end_date: Option<NaiveDate>,
+    end_date: Option<DateSpan>,
+
+    /// outline the margins and printable area of the page
+    #[argh(switch)]
+    debug_overlay: bool,
+
+    /// add checkbox fields that can be checked off in a PDF viewer
+    #[argh(switch)]
+    form: bool,
+
+    /// output filename, or - for stdout, for a single checklist
+    /// (default: daily_checklist_<yyyy-mm>.pdf)
+    #[argh(option, short = 'o')]
+    output: Option<PathBuf>,
+
+    /// file with a task on each line, like "Workout: mon wed fri" (default: built-in tasks)
+    #[argh(option)]
+    tasks: Option<PathBuf>,
+
+    /// generate the checklists again whenever the tasks file changes
+    #[argh(switch)]
+    watch: bool,
 }
 
 mod data {
     use chrono::Weekday;
-    use chrono::Weekday::{Fri, Mon, Thu, Tue, Wed};
+    use chrono::Weekday::{Fri, Mon, Sat, Sun, Thu, Tue, Wed};
     use lazy_static::lazy_static;
     use std::collections::HashSet;
 
@@ -50,6 +73,58 @@ mod data {
         some_days([Mon, Tue, Wed, Thu, Fri])
     }
 
+    // Parses a task list with one task on each line. The days that a task is done on follow a
+    // colon, like "Workout: mon wed fri" or "Inbox Zero: weekdays". A blank line is an empty
+    // column, and lines starting with '#' are comments.
+    pub fn parse_tasks(text: &str) -> Result<Vec<DailyTask<'_>>, String> {
+        let mut tasks = vec![];
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            let (name, days) = match line.split_once(':') {
+                Some((name, days)) => (
+                    name.trim(),
+                    parse_days(days.trim())
+                        .map_err(|err| format!("line {}: {}", index + 1, err))?,
+                ),
+                None => (line, None),
+            };
+            tasks.push(DailyTask { name, days });
+        }
+        let num_cols = super::DailyDescription::NUM_COLS;
+        if tasks.len() > num_cols {
+            return Err(format!(
+                "{} tasks won't fit in {} columns",
+                tasks.len(),
+                num_cols
+            ));
+        }
+        Ok(tasks)
+    }
+
+    fn parse_days(days: &str) -> Result<Option<HashSet<Weekday>>, String> {
+        let mut set = HashSet::new();
+        for day in days.split(|c: char| c == ',' || c.is_whitespace()) {
+            match day.to_lowercase().as_str() {
+                "" => {}
+                "daily" => return Ok(None),
+                "weekdays" => set.extend([Mon, Tue, Wed, Thu, Fri]),
+                "weekends" => set.extend([Sat, Sun]),
+                // The error doesn't quote the day, since the sheets server shows it to whoever
+                // asked for the sheet.
+                _ => {
+                    set.insert(day.parse::<Weekday>().map_err(|_| {
+                        "days are names like mon or tuesday, weekdays, weekends or daily"
+                            .to_string()
+                    })?);
+                }
+            }
+        }
+        Ok(Some(set))
+    }
+
     lazy_static! {
         pub static ref TASKS: Vec<DailyTask<'static>> = {
             vec![
@@ -158,26 +233,48 @@ mod data {
     }
 }
 
-struct DailyDescription {
+struct DailyDescription<'a> {
     bounds: WRect,
     dates_in_month: Vec<NaiveDate>,
+    tasks: &'a [data::DailyTask<'a>],
+    form: bool,
 }
 
-impl DailyDescription {
+impl<'a> DailyDescription<'a> {
     const NUM_COLS: usize = 25;
-
-    pub fn for_month<DL>(date: &DL, bounds: WRect) -> DailyDescription
-    where
-        DL: Datelike,
-    {
+    // Longer date ranges are split into pages of (at most) a month's worth of days.
+    const DAYS_PER_PAGE: usize = 31;
+
+    pub fn for_span(
+        span: &DateSpan,
+        tasks: &'a [data::DailyTask<'a>],
+        bounds: WRect,
+    ) -> DailyDescription<'a> {
         DailyDescription {
             bounds,
-            dates_in_month: date.dates_in_month(),
+            dates_in_month: span.dates(),
+            tasks,
+            form: false,
         }
     }
+
+    fn with_form(mut self, form: bool) -> Self {
+        self.form = form;
+        self
+    }
+
+    // False if the task in `col` isn't done on the day in `row`.
+    fn has_checkbox(&self, row: usize, col: usize) -> bool {
+        if col < self.tasks.len() {
+            if let Some(day_set) = &self.tasks[col].days {
+                return day_set.contains(&self.dates_in_month[row].weekday());
+            }
+        }
+        true
+    }
 }
 
-impl GridDescription for DailyDescription {
+impl GridDescription for DailyDescription<'_> {
     fn bounds(&self) -> WRect {
         self.bounds.clone()
     }
@@ -190,24 +287,32 @@ impl GridDescription for DailyDescription {
         Some(Self::NUM_COLS)
     }
 
+    fn rows_per_page(&self) -> Option<usize> {
+        Some(Self::DAYS_PER_PAGE)
+    }
+
     fn row_label_width(&self) -> Option<Unit> {
         Some(1.0.inches())
     }
 
     fn col_label_height(&self) -> Option<Unit> {
-        Some(2.0.inches())
+        Some(1.5.inches())
+    }
+
+    fn col_label_orientation(&self) -> LabelOrientation {
+        LabelOrientation::Angled(60.0)
     }
 
-    fn row_label(&self, index: usize) -> Cow<str> {
+    fn row_label(&self, index: usize) -> Cow<'_, str> {
         self.dates_in_month[index]
             .format("%b %e")
             .to_string()
             .into()
     }
 
-    fn col_label(&self, index: usize) -> Cow<str> {
-        if index < data::TASKS.len() {
-            data::TASKS[index].name.into()
+    fn col_label(&self, index: usize) -> Cow<'_, str> {
+        if index < self.tasks.len() {
+            self.tasks[index].name.into()
         } else {
             "".into()
         }
@@ -224,7 +329,7 @@ impl GridDescription for DailyDescription {
 
     fn vert_line_style(&self, col: usize, _num_cols: usize) -> Option<Attributes> {
         let attrs = Attributes::default();
-        if col > 0 && col < Self::NUM_COLS && col % 5 == 0 {
+        if col > 0 && col < Self::NUM_COLS && col.is_multiple_of(5) {
             Some(attrs)
         } else {
             Some(attrs.with_stroke_width(0.0))
@@ -232,7 +337,7 @@ impl GridDescription for DailyDescription {
     }
 
     fn column_background(&self, index: usize) -> Option<Color> {
-        if index % 2 == 0 {
+        if index.is_multiple_of(2) {
             Some(Colors::gray(0.9))
         } else {
             None
@@ -246,94 +351,211 @@ impl GridDescription for DailyDescription {
         cell_rect: &WRect,
         instructions: &mut Instructions,
     ) {
-        let mut should_draw_checkbox = true;
-        if col < data::TASKS.len() {
-            if let Some(day_set) = &data::TASKS[col].days {
-                let date = &self.dates_in_month[row];
-                if !day_set.contains(&date.weekday()) {
-                    instructions.set_fill_color(Colors::gray(0.7));
-                    // TODO: can we get rid of this clone()?
-                    instructions.push_shape(cell_rect.clone().fill());
-                    should_draw_checkbox = false;
-                }
-            }
+        if self.has_checkbox(row, col) {
+            Checkbox::new(3.0.mm())
+                .with_attributes(
+                    Attributes::default()
+                        .with_stroke_color(&Colors::gray(0.25))
+                        .with_stroke_width(0.0),
+                )
+                .render(cell_rect, instructions);
+        } else {
+            instructions.set_fill_color(Colors::gray(0.7));
+            // TODO: can we get rid of this clone()?
+            instructions.push_shape(cell_rect.clone().fill());
         }
+    }
 
-        if should_draw_checkbox {
-            render_checkbox(cell_rect, instructions);
+    fn cell_field(&self, row: usize, col: usize) -> Option<FormField> {
+        if !self.form || !self.has_checkbox(row, col) {
+            return None;
         }
+        // Named for the day and the task, like "2026-10-18 Plank", so saved forms can be read.
+        let task = match self.tasks.get(col) {
+            Some(task) if !task.name.is_empty() => task.name.to_string(),
+            _ => format!("Column {}", col + 1),
+        };
+        Some(FormField::checkbox(format!(
+            "{} {}",
+            self.dates_in_month[row].format("%Y-%m-%d"),
+            task
+        )))
     }
 }
 
-fn render_checkbox(cell_rect: &WRect, instructions: &mut Instructions) {
-    let box_width = 3.0.mm();
-
-    let x_offset = (cell_rect.width() - box_width) / 2;
-    let y_offset = (cell_rect.height() - box_width) / 2;
+fn render_dailies(
+    span: &DateSpan,
+    tasks: &[data::DailyTask],
+    form: bool,
+    _: &PdfDocumentReference,
+    page_setup: &PageSetup,
+) -> weekly::Result<Vec<Instructions>> {
+    // There is no binding gutter, so every page has the same content rect.
+    let grid_rect = page_setup.content_rect(1);
+    let description = DailyDescription::for_span(span, tasks, grid_rect).with_form(form);
+    let dates = description.dates_in_month.clone();
+    let mut pages = TGrid::with_description(description).paginate();
+
+    // Bookmark each page by month, and then by the days on it.
+    for (page, days) in pages
+        .iter_mut()
+        .zip(dates.chunks(DailyDescription::DAYS_PER_PAGE))
+    {
+        // unwrap: chunks are never empty.
+        let first = days.first().unwrap();
+        let last = days.last().unwrap();
+        page.bookmark(&[
+            first.format("%B %Y").to_string(),
+            format!("{} – {}", first.format("%b %-d"), last.format("%b %-d")),
+        ]);
+    }
+    Ok(pages)
+}
 
-    let checkbox_rect = WRect::with_dimensions(box_width, box_width)
-        .move_to(cell_rect.left() + x_offset, cell_rect.top() - y_offset);
+fn is_one_month(span: &DateSpan) -> bool {
+    span.is_whole_months() && span.months().len() == 1
+}
 
-    instructions.clear_fill_color();
-    instructions.set_stroke_color(Colors::gray(0.25));
-    instructions.set_stroke_width(0.0);
+fn default_output_filename(span: &DateSpan) -> PathBuf {
+    if is_one_month(span) {
+        format!("daily_checklist_{}.pdf", span.first().format("%Y-%m")).into()
+    } else {
+        format!(
+            "daily_checklist_{}_{}.pdf",
+            span.first().format("%Y-%m-%d"),
+            span.last().format("%Y-%m-%d")
+        )
+        .into()
+    }
+}
 
-    instructions.push_shape(checkbox_rect.stroke());
+fn default_doc_title(span: &DateSpan) -> String {
+    if is_one_month(span) {
+        format!("Daily Checklist - {}", span.first().format("%B %Y"))
+    } else {
+        format!(
+            "Daily Checklist - {} to {}",
+            span.first().format("%b %-d, %Y"),
+            span.last().format("%b %-d, %Y")
+        )
+    }
 }
 
-fn render_dailies(
-    date: &NaiveDate,
-    end_date: &Option<NaiveDate>,
-    _: &PdfDocumentReference,
-    page_rect: &WRect,
-) -> weekly::Result<Instructions> {
-    let grid_rect =
-        page_rect.inset_all_q1(0.25.inches(), 0.25.inches(), 0.6.inches(), 0.25.inches());
-    let description = if let Some(end) = end_date {
-        DailyDescription {
-            bounds: grid_rect,
-            dates_in_month: date.date_range((*end - *date).num_days()),
-        }
+// Whole months are split into a checklist each. Any other span is a single checklist, except that
+// a single day means its month, as it always has.
+fn checklist_spans(span: &DateSpan) -> Vec<DateSpan> {
+    if span.num_days() == 1 {
+        vec![DateSpan::month(&span.first())]
+    } else if span.is_whole_months() {
+        span.months().iter().map(DateSpan::month).collect()
     } else {
-        DailyDescription::for_month(date, grid_rect)
-    };
-    let grid = TGrid::with_description(description);
-    Ok(grid.generate_instructions())
+        vec![*span]
+    }
 }
 
-fn default_output_filename(date: &NaiveDate) -> PathBuf {
-    format!("daily_checklist_{}.pdf", date.format("%Y-%m")).into()
+fn page_setup(debug_overlay: bool) -> PageSetup {
+    PageSetup::new(&sizes::letter())
+        .with_margins(Margins::all(0.25.inches()))
+        // Our printer can't reach the right-hand edge of the page.
+        .with_non_printable(Margins::new(
+            Unit::zero(),
+            Unit::zero(),
+            0.6.inches(),
+            Unit::zero(),
+        ))
+        .with_debug_overlay(debug_overlay)
 }
 
-fn default_doc_title(date: &NaiveDate) -> String {
-    format!("Daily Checklist - {}", date.format("%B %Y"))
+fn main_func(
+    span: &DateSpan,
+    tasks: &[data::DailyTask],
+    args: &Args,
+    page_setup: &PageSetup,
+) -> Result<()> {
+    let output_filename = args
+        .output
+        .clone()
+        .unwrap_or_else(|| default_output_filename(span));
+    let doc_title = default_doc_title(span);
+
+    save_document(&doc_title, output_filename, page_setup, |d, p| {
+        render_dailies(span, tasks, args.form, d, p)
+    })
 }
 
-fn main_func(date: &NaiveDate, end: &Option<NaiveDate>) -> Result<()> {
-    let output_filename = default_output_filename(date);
-    let doc_title = default_doc_title(date);
+// The checklists that the arguments ask for.
+fn requested_spans(args: &Args) -> Result<Vec<DateSpan>> {
+    if let (Some(start), Some(end)) = (args.start_date, args.end_date) {
+        if end.last() < start.first() {
+            return Err(WeeklyError::BadDate(
+                "the end date is before the start date".to_string(),
+            ));
+        }
+        Ok(vec![DateSpan::new(start.first(), end.last())])
+    } else if args.dates.is_empty() {
+        Ok(vec![DateSpan::month(&weekly::today())])
+    } else {
+        Ok(args.dates.iter().flat_map(checklist_spans).collect())
+    }
+}
 
-    save_one_page_document(&doc_title, output_filename, &sizes::letter(), |d, p| {
-        render_dailies(date, end, d, p)
-    })
+// Reads the tasks and generates every checklist. An error for one date doesn't stop the others,
+// but is still an error when they are done.
+fn generate(args: &Args, page_setup: &PageSetup) -> Result<()> {
+    let text = match &args.tasks {
+        Some(path) => Some(std::fs::read_to_string(path)?),
+        None => None,
+    };
+    let parsed;
+    let tasks: &[data::DailyTask] = match (&args.tasks, &text) {
+        (Some(path), Some(text)) => {
+            parsed = data::parse_tasks(text)
+                .map_err(|err| WeeklyError::ConfigError(format!("{}, {}", path.display(), err)))?;
+            &parsed
+        }
+        _ => &data::TASKS,
+    };
+
+    let spans = requested_spans(args)?;
+    let mut num_failed = 0;
+    for span in &spans {
+        if let Err(err) = main_func(span, tasks, args, page_setup) {
+            eprintln!("Error: {} : {}", default_doc_title(span), err);
+            num_failed += 1;
+        }
+    }
+    if num_failed > 0 {
+        return Err(WeeklyError::ConfigError(format!(
+            "{} of {} checklists failed",
+            num_failed,
+            spans.len()
+        )));
+    }
+    Ok(())
 }
 
 fn main() {
     let args: Args = argh::from_env();
+    let page_setup = page_setup(args.debug_overlay);
+    let num_checklists = requested_spans(&args).map_or(0, |spans| spans.len());
+    if args.output.is_some() && num_checklists > 1 {
+        eprintln!(
+            "Each date makes its own checklist, so --output can't be used with more than one."
+        );
+        std::process::exit(1);
+    }
 
-    if args.start_date.is_some() && args.end_date.is_some() {
-        if let Err(err) = main_func(&args.start_date.unwrap(), &args.end_date) {
-            eprintln!("Error: {:?}", err);
-        }
-    } else if args.dates.is_empty() {
-        if let Err(err) = main_func(&weekly::today(), &None) {
-            eprintln!("Error: {:?}", err);
-        }
-    } else {
-        for date in &args.dates {
-            if let Err(err) = main_func(date, &None) {
-                eprintln!("Error: {} : {:?}", date.format("%Y-%m"), err);
+    if args.watch {
+        match &args.tasks {
+            Some(path) => watch_files(&[path], || generate(&args, &page_setup)),
+            None => {
+                eprintln!("--watch needs a --tasks file to watch.");
+                std::process::exit(1);
             }
         }
     }
+    if let Err(err) = generate(&args, &page_setup) {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,257 @@
+use argh::FromArgs;
+use chrono::{NaiveDate, Weekday};
+use std::borrow::Cow;
+use std::path::PathBuf;
+use weekly::cells::{CellAlignment, CellRenderer};
+use weekly::patterns::{Pattern, RuleHeight, RuledPaper};
+use weekly::{
+    save_document, sizes, Attributes, Colors, DateSpan, Datetools, FontProxy, GridDescription,
+    Instructions, Layout, Margins, MonthCalendar, NumericUnit, PageSetup, Result, TGrid,
+    TextContext, Unit, WRect,
+};
+
+#[derive(FromArgs)]
+/// Prints a daily planner page for each day: a schedule, top priorities and notes.
+struct Args {
+    /// the days to print, like 2026-10-18, this-week or 2026-10-18..+2w (default: today)
+    #[argh(option, short = 's')]
+    start: Option<DateSpan>,
+
+    /// the last day (default: the last day of --start)
+    #[argh(option, short = 'e')]
+    end: Option<DateSpan>,
+
+    /// hour that the schedule starts, 0-23 (default: 7)
+    #[argh(option, default = "7")]
+    first_hour: u32,
+
+    /// hour that the schedule ends, 1-24 (default: 21)
+    #[argh(option, default = "21")]
+    last_hour: u32,
+
+    /// number of slots in each hour: 1, 2 for half-hours, 4 for quarter-hours (default: 2)
+    #[argh(option, default = "2")]
+    subdivisions: usize,
+
+    /// the day that weeks start on in the small calendar (default: sunday)
+    #[argh(option, default = "Weekday::Sun", from_str_fn(parse_weekday))]
+    first_day: Weekday,
+
+    /// output filename, or - for stdout (default: dayplanner-<start>.pdf)
+    #[argh(option, short = 'o')]
+    output: Option<PathBuf>,
+}
+
+fn parse_weekday(value: &str) -> std::result::Result<Weekday, String> {
+    value
+        .parse()
+        .map_err(|_| format!("unknown day of the week: {}", value))
+}
+
+// The hourly schedule. Hours are divided into `subdivisions` rows, with a label on the first.
+struct ScheduleDescription {
+    bounds: WRect,
+    first_hour: u32,
+    last_hour: u32,
+    subdivisions: usize,
+}
+
+impl GridDescription for ScheduleDescription {
+    fn bounds(&self) -> WRect {
+        self.bounds.clone()
+    }
+
+    fn num_rows(&self) -> Option<usize> {
+        Some((self.last_hour - self.first_hour) as usize * self.subdivisions)
+    }
+
+    fn num_cols(&self) -> Option<usize> {
+        Some(1)
+    }
+
+    fn row_label_width(&self) -> Option<Unit> {
+        Some(0.5.inches())
+    }
+
+    fn row_label(&self, index: usize) -> Cow<'_, str> {
+        if index.is_multiple_of(self.subdivisions) {
+            // 12-hour clock, without AM/PM.
+            let hour = self.first_hour + (index / self.subdivisions) as u32;
+            ((hour + 11) % 12 + 1).to_string().into()
+        } else {
+            "".into()
+        }
+    }
+
+    fn horiz_line_style(&self, index: usize, _num_rows: usize) -> Option<Attributes> {
+        if index.is_multiple_of(self.subdivisions) {
+            Some(Attributes::default().with_stroke_width(0.5))
+        } else {
+            Some(
+                Attributes::default()
+                    .with_stroke_width(0.0)
+                    .with_stroke_color(&Colors::gray(0.6))
+                    .with_dash(1, 2),
+            )
+        }
+    }
+
+    fn vert_line_style(&self, _index: usize, _num_cols: usize) -> Option<Attributes> {
+        Some(Attributes::default().with_stroke_width(0.5))
+    }
+
+    fn font(&self) -> FontProxy {
+        FontProxy::helvetica()
+    }
+}
+
+// Draws a section heading at the top of `rect`, and returns the rest of the rect.
+fn render_heading(heading: &str, rect: &WRect, instructions: &mut Instructions) -> WRect {
+    let font = FontProxy::helvetica_bold();
+    let font_size = 12.0;
+    instructions.push_text(
+        heading,
+        font_size,
+        rect.left(),
+        rect.top() - font.cap_height(font_size),
+        font,
+    );
+    rect.inset_all_q1(Unit::zero(), 0.3.inches(), Unit::zero(), Unit::zero())
+}
+
+fn render_header(
+    date: &NaiveDate,
+    first_day: Weekday,
+    rect: &WRect,
+    instructions: &mut Instructions,
+) {
+    let font = FontProxy::helvetica_bold();
+    let font_size = 36.0;
+    let weekday_top = rect.top() - font.cap_height(font_size);
+    instructions.push_text(
+        &date.format("%A").to_string(),
+        font_size,
+        rect.left(),
+        weekday_top,
+        font,
+    );
+    instructions.push_text(
+        &date.format("%B %-d, %Y").to_string(),
+        font_size / 2.0,
+        rect.left(),
+        weekday_top - 0.375.inches(),
+        FontProxy::helvetica(),
+    );
+
+    let calendar_width = 1.75.inches();
+    let calendar_rect = WRect::with_dimensions(calendar_width, rect.height())
+        .move_to(rect.right() - calendar_width, rect.top());
+    MonthCalendar::new(date)
+        .with_first_weekday(first_day)
+        .with_six_weeks(true)
+        .with_adjacent_days(false)
+        .with_day_numbers(CellAlignment::Center, 45.0)
+        .with_line_attributes(None)
+        .render(&calendar_rect, instructions);
+}
+
+fn render_priorities(rect: &WRect, instructions: &mut Instructions) {
+    let rect = render_heading("Top priorities", rect, instructions);
+    RuledPaper::new(RuleHeight::Custom(rect.height() / 3))
+        .with_line_numbers(&TextContext::helvetica().with_text_height(12.0.mm()))
+        .with_line_attributes(Attributes::default().with_stroke_width(0.5))
+        .render(&rect, instructions);
+}
+
+fn render_notes(rect: &WRect, instructions: &mut Instructions) {
+    let rect = render_heading("Notes", rect, instructions);
+    RuledPaper::new(RuleHeight::College)
+        .with_line_attributes(
+            Attributes::default()
+                .with_stroke_width(0.0)
+                .with_stroke_color(&Colors::gray(0.6)),
+        )
+        .render(&rect, instructions);
+}
+
+fn render_day(args: &Args, date: &NaiveDate, rect: &WRect) -> Instructions {
+    let mut instructions = Instructions::default();
+    instructions.bookmark(&[
+        date.format("%Y").to_string(),
+        date.format("%B").to_string(),
+        date.format("%A %-d").to_string(),
+    ]);
+
+    let page = Layout::rows()
+        .with_gap(0.25.inches())
+        .fixed("header", 1.25.inches())
+        .flex("body", 1.0)
+        .split(rect);
+    render_header(date, args.first_day, &page["header"], &mut instructions);
+
+    let body = Layout::columns()
+        .with_gap(0.25.inches())
+        .flex("schedule", 1.0)
+        .flex("right", 1.0)
+        .split(&page["body"]);
+
+    let schedule_rect = render_heading("Schedule", &body["schedule"], &mut instructions);
+    TGrid::with_description(ScheduleDescription {
+        bounds: schedule_rect,
+        first_hour: args.first_hour,
+        last_hour: args.last_hour,
+        subdivisions: args.subdivisions,
+    })
+    .append_to_instructions(&mut instructions);
+
+    let right = Layout::rows()
+        .with_gap(0.25.inches())
+        .fixed("priorities", 1.5.inches())
+        .flex("notes", 1.0)
+        .split(&body["right"]);
+    render_priorities(&right["priorities"], &mut instructions);
+    render_notes(&right["notes"], &mut instructions);
+
+    instructions
+}
+
+fn main() -> Result<()> {
+    let args: Args = argh::from_env();
+
+    if args.first_hour >= args.last_hour || args.last_hour > 24 {
+        eprintln!(
+            "The schedule must start before it ends, within 0-24: {}-{}",
+            args.first_hour, args.last_hour
+        );
+        std::process::exit(1);
+    }
+    if args.subdivisions == 0 {
+        eprintln!("Every hour needs at least one slot.");
+        std::process::exit(1);
+    }
+
+    let span = args
+        .start
+        .unwrap_or_else(|| DateSpan::day(&weekly::today()));
+    let start = span.first();
+    let end = args.end.map_or(span.last(), |end| end.last());
+    let num_days = end.signed_duration_since(start).num_days() + 1;
+    if num_days < 1 {
+        eprintln!("The last day, {}, is before the first, {}.", end, start);
+        std::process::exit(1);
+    }
+
+    let page_setup = PageSetup::new(&sizes::letter()).with_margins(Margins::all(0.5.inches()));
+    let output = args
+        .output
+        .clone()
+        .unwrap_or_else(|| format!("dayplanner-{}.pdf", start.format("%Y-%m-%d")).into());
+    save_document("Daily planner", output, &page_setup, |_, page_setup| {
+        Ok(start
+            .date_range(num_days)
+            .iter()
+            .enumerate()
+            .map(|(index, date)| render_day(&args, date, &page_setup.content_rect(index + 1)))
+            .collect())
+    })
+}
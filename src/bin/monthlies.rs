@@ -5,16 +5,38 @@ use std::borrow::Cow;
 use std::path::PathBuf;
 use weekly::FontProxy;
 use weekly::{
-    save_one_page_document, Colors, Datetools, GridDescription, Instructions, NumericUnit, TGrid,
-    Unit, WRect,
+    save_document, watch_files, Colors, DateSpan, Datetools, GridDescription, Instructions,
+    LabelOrientation, Margins, NumericUnit, PageSetup, TGrid, Unit, WRect, WeeklyError,
 };
 
 #[derive(FromArgs)]
 #[argh(description = "Creates a checklist of monthly tasks.")]
 struct MonthlyArgs {
-    // todo: allow use of yyyy-mm for start
-    #[argh(option, long = "start", description = "the start month (yyyy-mm-dd)")]
-    start_date: Option<NaiveDate>,
+    #[argh(
+        option,
+        long = "start",
+        description = "the start month, like 2026-10 or next-month (default: this month)"
+    )]
+    start_date: Option<DateSpan>,
+
+    #[argh(
+        option,
+        short = 'o',
+        description = "output filename, or - for stdout (default: monthlies-<start>.pdf)"
+    )]
+    output: Option<PathBuf>,
+
+    #[argh(
+        option,
+        description = "file with a task on each line, '#' for comments (default: built-in tasks)"
+    )]
+    tasks: Option<PathBuf>,
+
+    #[argh(
+        switch,
+        description = "generate the checklist again whenever the tasks file changes"
+    )]
+    watch: bool,
 }
 
 fn names_for_months(start_date: &NaiveDate, n: usize) -> Vec<String> {
@@ -42,6 +64,7 @@ fn default_doc_title(date: &NaiveDate) -> String {
 struct MonthlyDescription {
     bounds: WRect,
     month_names: Vec<String>,
+    row_labels: Vec<String>,
 }
 
 impl MonthlyDescription {
@@ -63,13 +86,41 @@ impl MonthlyDescription {
         "Run FI simulation",
     ];
 
-    pub fn for_start_month<DL>(date: &DL, grid_rect: &WRect) -> MonthlyDescription
+    pub fn for_start_month<DL>(
+        date: &DL,
+        row_labels: Vec<String>,
+        grid_rect: &WRect,
+    ) -> MonthlyDescription
     where
         DL: Datelike,
     {
         MonthlyDescription {
             bounds: grid_rect.clone(),
             month_names: names_for_months(&date.first_of_month(), Self::NUM_ROWS),
+            row_labels,
+        }
+    }
+
+    fn default_row_labels() -> Vec<String> {
+        Self::ROW_LABELS.iter().map(|s| s.to_string()).collect()
+    }
+
+    // One task on each line. Blank lines are empty rows, and lines starting with '#' are comments.
+    fn parse_row_labels(text: &str) -> std::result::Result<Vec<String>, String> {
+        let labels: Vec<String> = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with('#'))
+            .map(String::from)
+            .collect();
+        if labels.len() > Self::NUM_ROWS {
+            Err(format!(
+                "{} tasks won't fit in {} rows",
+                labels.len(),
+                Self::NUM_ROWS
+            ))
+        } else {
+            Ok(labels)
         }
     }
 }
@@ -92,23 +143,27 @@ impl GridDescription for MonthlyDescription {
     }
 
     fn col_label_height(&self) -> Option<Unit> {
-        Some(1.0.inches())
+        Some(0.75.inches())
+    }
+
+    fn col_label_orientation(&self) -> LabelOrientation {
+        LabelOrientation::Angled(45.0)
     }
 
-    fn row_label(&self, index: usize) -> Cow<str> {
-        if index < Self::ROW_LABELS.len() {
-            Self::ROW_LABELS[index].into()
+    fn row_label(&self, index: usize) -> Cow<'_, str> {
+        if index < self.row_labels.len() {
+            self.row_labels[index].as_str().into()
         } else {
             "".into()
         }
     }
 
-    fn col_label(&self, index: usize) -> Cow<str> {
+    fn col_label(&self, index: usize) -> Cow<'_, str> {
         self.month_names[index].as_str().into()
     }
 
     fn column_background(&self, index: usize) -> Option<Color> {
-        if index % 2 == 0 {
+        if index.is_multiple_of(2) {
             Some(Colors::gray(0.9))
         } else {
             None
@@ -122,26 +177,52 @@ impl GridDescription for MonthlyDescription {
 
 fn render_monthlies(
     date: &NaiveDate,
+    row_labels: Vec<String>,
     _: &PdfDocumentReference,
-    page_rect: &WRect,
-) -> weekly::Result<Instructions> {
-    let table_bounds =
-        page_rect.inset_all_q1(0.25.inches(), 0.25.inches(), 0.25.inches(), 0.25.inches());
+    page_setup: &PageSetup,
+) -> weekly::Result<Vec<Instructions>> {
+    let table_bounds = page_setup.content_rect(1);
 
-    let description = MonthlyDescription::for_start_month(date, &table_bounds);
+    let description = MonthlyDescription::for_start_month(date, row_labels, &table_bounds);
     let grid = TGrid::with_description(description);
-    Ok(grid.generate_instructions())
+    Ok(vec![grid.generate_instructions()])
+}
+
+// Reads the tasks and generates the checklist.
+fn generate(args: &MonthlyArgs) -> weekly::Result<()> {
+    let row_labels = match &args.tasks {
+        Some(path) => MonthlyDescription::parse_row_labels(&std::fs::read_to_string(path)?)
+            .map_err(|err| WeeklyError::ConfigError(format!("{}, {}", path.display(), err)))?,
+        None => MonthlyDescription::default_row_labels(),
+    };
+
+    let date = args
+        .start_date
+        .map_or_else(weekly::today, |span| span.first());
+    let title = default_doc_title(&date);
+    let filename = args
+        .output
+        .clone()
+        .unwrap_or_else(|| default_output_filename(&date));
+
+    let page_setup =
+        PageSetup::new(&weekly::sizes::letter()).with_margins(Margins::all(0.25.inches()));
+    save_document(&title, filename, &page_setup, |d, p| {
+        render_monthlies(&date, row_labels, d, p)
+    })
 }
 
 fn main() -> weekly::Result<()> {
     let args: MonthlyArgs = argh::from_env();
 
-    let date = args.start_date.unwrap_or_else(weekly::today);
-    let title = default_doc_title(&date);
-    let filename = default_output_filename(&date);
-
-    let page_bounds = weekly::sizes::letter();
-    save_one_page_document(&title, filename, &page_bounds, |d, r| {
-        render_monthlies(&date, d, r)
-    })
+    if args.watch {
+        match &args.tasks {
+            Some(path) => watch_files(&[path], || generate(&args)),
+            None => {
+                eprintln!("--watch needs a --tasks file to watch.");
+                std::process::exit(1);
+            }
+        }
+    }
+    generate(&args)
 }
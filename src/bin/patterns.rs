@@ -0,0 +1,356 @@
+use argh::FromArgs;
+use std::path::PathBuf;
+use weekly::patterns::{
+    DotGrid, GraphPaper, HexGrid, HolePunch, IsometricGrid, MusicStaff, Pattern, PatternAlignment,
+    RuleHeight, RuledPaper, SquareGrid,
+};
+use weekly::{
+    save_document, sizes, Attributes, Colors, Instructions, Margins, NumericUnit, PageSetup,
+    Result, TextContext, WRect,
+};
+
+#[derive(FromArgs)]
+/// Prints a full sheet of a background pattern.
+struct Args {
+    /// output filename, or - for stdout (default: <pattern>.pdf)
+    #[argh(option, short = 'o')]
+    output: Option<PathBuf>,
+
+    /// paper size: letter, halfletter, legal, tableau, a4 or remarkable2 (default: letter)
+    #[argh(option, default = "String::from(\"letter\")")]
+    paper: String,
+
+    /// margin around the pattern, in inches (default: 0.25)
+    #[argh(option, default = "0.25")]
+    margin: f64,
+
+    /// how far the pattern runs past the edge of the paper, in inches, to be trimmed off
+    /// (default: 0)
+    #[argh(option, default = "0.0")]
+    bleed: f64,
+
+    /// add crop marks outside the corners of the paper
+    #[argh(switch)]
+    crop_marks: bool,
+
+    /// gray level of the pattern, from 0 (black) to 1 (white) (default: 0.7)
+    #[argh(option, default = "0.7", from_str_fn(parse_gray))]
+    gray: f64,
+
+    /// line weight, in points (default: 0.5)
+    #[argh(option, default = "0.5")]
+    weight: f64,
+
+    /// center the pattern on the page instead of starting at the top-left
+    #[argh(switch)]
+    center: bool,
+
+    #[argh(subcommand)]
+    pattern: PatternCommand,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum PatternCommand {
+    Dots(DotsArgs),
+    Squares(SquaresArgs),
+    Graph(GraphArgs),
+    Isometric(IsometricArgs),
+    Hex(HexArgs),
+    Staff(StaffArgs),
+    Ruled(RuledArgs),
+}
+
+#[derive(FromArgs)]
+/// A dot grid.
+#[argh(subcommand, name = "dots")]
+struct DotsArgs {
+    /// distance between dots, in mm (default: 5)
+    #[argh(option, default = "5.0", from_str_fn(parse_pitch))]
+    pitch: f64,
+
+    /// dot radius, in mm (default: 0.25)
+    #[argh(option, default = "0.25")]
+    radius: f64,
+}
+
+#[derive(FromArgs)]
+/// A square grid.
+#[argh(subcommand, name = "squares")]
+struct SquaresArgs {
+    /// size of the squares, in mm (default: 5)
+    #[argh(option, default = "5.0", from_str_fn(parse_pitch))]
+    pitch: f64,
+}
+
+#[derive(FromArgs)]
+/// Graph paper with major and minor lines.
+#[argh(subcommand, name = "graph")]
+struct GraphArgs {
+    /// distance between minor lines, in mm (default: 1)
+    #[argh(option, default = "1.0", from_str_fn(parse_pitch))]
+    pitch: f64,
+
+    /// number of minor squares between major lines (default: 10)
+    #[argh(option, default = "10")]
+    major_every: usize,
+}
+
+#[derive(FromArgs)]
+/// An isometric grid of lines or dots.
+#[argh(subcommand, name = "isometric")]
+struct IsometricArgs {
+    /// distance between neighboring points, in mm (default: 5)
+    #[argh(option, default = "5.0", from_str_fn(parse_pitch))]
+    pitch: f64,
+
+    /// draw only the dots, not the lines
+    #[argh(switch)]
+    dots: bool,
+}
+
+#[derive(FromArgs)]
+/// A hexagon grid.
+#[argh(subcommand, name = "hex")]
+struct HexArgs {
+    /// length of a side of a hexagon, in mm (default: 5)
+    #[argh(option, default = "5.0", from_str_fn(parse_pitch))]
+    side: f64,
+}
+
+#[derive(FromArgs)]
+/// Music staff paper.
+#[argh(subcommand, name = "staff")]
+struct StaffArgs {
+    /// distance between the lines of a staff, in mm (default: 2)
+    #[argh(option, default = "2.0", from_str_fn(parse_pitch))]
+    line_spacing: f64,
+
+    /// space between staves, in mm (default: 6 times the line spacing)
+    #[argh(option, from_str_fn(parse_gap))]
+    staff_gap: Option<f64>,
+}
+
+#[derive(FromArgs)]
+/// Ruled paper.
+#[argh(subcommand, name = "ruled")]
+struct RuledArgs {
+    /// rule height: college, wide, narrow, gregg, or a height of at least 0.5 mm (default:
+    /// college)
+    #[argh(option, default = "String::from(\"college\")")]
+    rule: String,
+
+    /// draw a margin line this many inches in from the left
+    #[argh(option)]
+    margin_line: Option<f64>,
+
+    /// leave a header band this many inches tall at the top
+    #[argh(option)]
+    header: Option<f64>,
+
+    /// draw guides for 2 or 3 punched holes
+    #[argh(option)]
+    holes: Option<usize>,
+
+    /// number the lines
+    #[argh(switch)]
+    numbers: bool,
+}
+
+// Anything finer than this takes forever to draw, and prints as a solid gray.
+const MIN_PITCH_MM: f64 = 0.5;
+
+fn parse_pitch(value: &str) -> std::result::Result<f64, String> {
+    let pitch: f64 = value.parse().map_err(|_| "not a number".to_string())?;
+    if pitch >= MIN_PITCH_MM {
+        Ok(pitch)
+    } else {
+        Err(format!("must be at least {} mm", MIN_PITCH_MM))
+    }
+}
+
+fn parse_gap(value: &str) -> std::result::Result<f64, String> {
+    let gap: f64 = value.parse().map_err(|_| "not a number".to_string())?;
+    if gap >= 0.0 {
+        Ok(gap)
+    } else {
+        Err("can't be negative".to_string())
+    }
+}
+
+fn parse_gray(value: &str) -> std::result::Result<f64, String> {
+    let gray: f64 = value.parse().map_err(|_| "not a number".to_string())?;
+    if (0.0..=1.0).contains(&gray) {
+        Ok(gray)
+    } else {
+        Err("must be from 0 to 1".to_string())
+    }
+}
+
+fn rule_height(name: &str) -> Option<RuleHeight> {
+    match name {
+        "college" => Some(RuleHeight::College),
+        "wide" => Some(RuleHeight::Wide),
+        "narrow" => Some(RuleHeight::Narrow),
+        "gregg" => Some(RuleHeight::Gregg),
+        _ => name
+            .parse::<f64>()
+            .ok()
+            .filter(|height| *height >= MIN_PITCH_MM)
+            .map(|height| RuleHeight::Custom(height.mm())),
+    }
+}
+
+fn paper_size(name: &str) -> Option<WRect> {
+    match name {
+        "letter" => Some(sizes::letter()),
+        "halfletter" => Some(sizes::halfletter()),
+        "legal" => Some(sizes::legal()),
+        "tableau" => Some(sizes::tableau()),
+        "a4" => Some(sizes::a4()),
+        "remarkable2" => Some(sizes::remarkable2()),
+        _ => None,
+    }
+}
+
+impl PatternCommand {
+    fn name(&self) -> &'static str {
+        match self {
+            PatternCommand::Dots(_) => "dots",
+            PatternCommand::Squares(_) => "squares",
+            PatternCommand::Graph(_) => "graph",
+            PatternCommand::Isometric(_) => "isometric",
+            PatternCommand::Hex(_) => "hex",
+            PatternCommand::Staff(_) => "staff",
+            PatternCommand::Ruled(_) => "ruled",
+        }
+    }
+
+    // `paper` is the trim rect of the page, for anything measured from the edge of the paper.
+    fn pattern(
+        &self,
+        alignment: PatternAlignment,
+        attributes: Attributes,
+        paper: &WRect,
+    ) -> Box<dyn Pattern> {
+        match self {
+            PatternCommand::Dots(args) => Box::new(
+                DotGrid::new(args.pitch.mm())
+                    .with_radius(args.radius.mm())
+                    .with_alignment(alignment)
+                    .with_attributes(attributes),
+            ),
+            PatternCommand::Squares(args) => Box::new(
+                SquareGrid::new(args.pitch.mm())
+                    .with_alignment(alignment)
+                    .with_attributes(attributes),
+            ),
+            PatternCommand::Graph(args) => Box::new(
+                GraphPaper::new(args.pitch.mm(), args.major_every)
+                    .with_alignment(alignment)
+                    .with_minor_attributes(attributes.clone().with_stroke_width(0.0))
+                    .with_major_attributes(attributes),
+            ),
+            PatternCommand::Isometric(args) => {
+                let grid = if args.dots {
+                    IsometricGrid::dots(args.pitch.mm())
+                } else {
+                    IsometricGrid::lines(args.pitch.mm())
+                };
+                Box::new(grid.with_alignment(alignment).with_attributes(attributes))
+            }
+            PatternCommand::Hex(args) => Box::new(
+                HexGrid::new(args.side.mm())
+                    .with_alignment(alignment)
+                    .with_attributes(attributes),
+            ),
+            PatternCommand::Staff(args) => {
+                let staff = MusicStaff::new(args.line_spacing.mm())
+                    .with_alignment(alignment)
+                    .with_attributes(attributes);
+                Box::new(match args.staff_gap {
+                    Some(gap) => staff.with_staff_gap(gap.mm()),
+                    None => staff,
+                })
+            }
+            PatternCommand::Ruled(args) => {
+                let rule_height = match rule_height(&args.rule) {
+                    Some(rule_height) => rule_height,
+                    None => {
+                        eprintln!("Unknown rule height: {}", args.rule);
+                        std::process::exit(1);
+                    }
+                };
+                let mut ruled = RuledPaper::new(rule_height).with_line_attributes(attributes);
+                if let Some(offset) = args.margin_line {
+                    ruled = ruled.with_margin_line(offset.inches());
+                }
+                if let Some(height) = args.header {
+                    ruled = ruled.with_header(height.inches());
+                }
+                match args.holes {
+                    None => {}
+                    Some(2) => {
+                        ruled = ruled.with_hole_punch(HolePunch::two_hole().with_paper(paper))
+                    }
+                    Some(3) => {
+                        ruled = ruled.with_hole_punch(HolePunch::three_hole().with_paper(paper))
+                    }
+                    Some(holes) => {
+                        eprintln!("Only 2 or 3 holes are supported, not {}", holes);
+                        std::process::exit(1);
+                    }
+                }
+                if args.numbers {
+                    ruled = ruled
+                        .with_line_numbers(&TextContext::helvetica().with_text_height(6.0.mm()));
+                }
+                Box::new(ruled)
+            }
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let args: Args = argh::from_env();
+
+    let paper = match paper_size(&args.paper) {
+        Some(paper) => paper,
+        None => {
+            eprintln!("Unknown paper size: {}", args.paper);
+            std::process::exit(1);
+        }
+    };
+    if args.bleed < 0.0 {
+        eprintln!("The bleed can't be negative: {}", args.bleed);
+        std::process::exit(1);
+    }
+    let page_setup = PageSetup::new(&paper)
+        .with_margins(Margins::all(args.margin.inches()))
+        .with_bleed(args.bleed.inches())
+        .with_crop_marks(args.crop_marks);
+
+    let alignment = if args.center {
+        PatternAlignment::Center
+    } else {
+        PatternAlignment::TopLeft
+    };
+    let color = Colors::gray(args.gray);
+    let attributes = Attributes::default()
+        .with_stroke_width(args.weight)
+        .with_stroke_color(&color)
+        .with_fill_color(&color);
+    let pattern = args
+        .pattern
+        .pattern(alignment, attributes, &page_setup.trim_rect());
+
+    let name = args.pattern.name();
+    let output = args
+        .output
+        .unwrap_or_else(|| PathBuf::from(format!("{}.pdf", name)));
+    save_document(name, output, &page_setup, |_, page_setup| {
+        let mut instructions = Instructions::default();
+        pattern.render(&page_setup.content_rect(1), &mut instructions);
+        Ok(vec![instructions])
+    })
+}
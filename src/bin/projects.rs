@@ -1,46 +1,54 @@
+use argh::FromArgs;
 use printpdf::PdfDocumentReference;
+use std::path::PathBuf;
+use weekly::patterns::{Pattern, RuleHeight, RuledPaper};
 use weekly::{
-    save_one_page_document, Colors, HasRenderAttrs, Instructions, NumericUnit, Unit, WLine, WRect,
+    save_document, Attributes, Colors, HasRenderAttrs, Instructions, Layout, Margins, NumericUnit,
+    PageSetup, WLine, WRect,
 };
 
-fn render_projects(_: &PdfDocumentReference, page_bounds: &WRect) -> weekly::Result<Instructions> {
-    let content_bounds =
-        page_bounds.inset_all_q1(0.325.inches(), 0.25.inches(), 0.25.inches(), 0.25.inches());
+#[derive(FromArgs)]
+/// Prints a page of four project cards, to be cut apart.
+struct Args {
+    /// output filename, or - for stdout (default: projects.pdf)
+    #[argh(option, short = 'o', default = "PathBuf::from(\"projects.pdf\")")]
+    output: PathBuf,
+}
 
-    let top_left = content_bounds.resize(
-        content_bounds.width() / 2 - 0.0625.inches(),
-        content_bounds.height() / 2 - 0.0625.inches(),
-    );
-    let top_right = top_left.move_to(
-        content_bounds.left() + content_bounds.width() / 2 + 0.0625.inches(),
-        top_left.top(),
-    );
-    let bottom_left = top_left.move_to(
-        top_left.left(),
-        content_bounds.height() / 2 + 0.125.inches(),
-    );
-    let bottom_right = top_right.move_to(
-        top_right.left(),
-        content_bounds.height() / 2 + 0.125.inches(),
-    );
+fn render_projects(
+    _: &PdfDocumentReference,
+    page_setup: &PageSetup,
+) -> weekly::Result<Vec<Instructions>> {
+    let content_bounds = page_setup.content_rect(1);
+
+    let gutter = 0.125.inches();
+    let rows = Layout::rows()
+        .with_gap(gutter)
+        .repeat("row", 2)
+        .split(&content_bounds);
+    let columns = Layout::columns().with_gap(gutter).repeat("col", 2);
 
     let mut instructions = Instructions::default();
-    fill_project_into_rect(top_left, &mut instructions);
-    fill_project_into_rect(top_right, &mut instructions);
-    fill_project_into_rect(bottom_left, &mut instructions);
-    fill_project_into_rect(bottom_right, &mut instructions);
+    for row in rows.iter() {
+        for card in columns.split(row).iter() {
+            fill_project_into_rect(card.clone(), &mut instructions);
+        }
+    }
 
-    Ok(instructions)
+    Ok(vec![instructions])
 }
 
 fn main() -> weekly::Result<()> {
+    let args: Args = argh::from_env();
     let doc_title = "Project template";
-    let output_filename = "projects.pdf";
 
-    let page_bounds =
+    let trim_size =
         WRect::with_dimensions(5.5.inches(), 8.5.inches()).move_to(0.0.inches(), 8.5.inches());
+    let page_setup = PageSetup::new(&trim_size)
+        .with_margins(Margins::all(0.25.inches()))
+        .with_binding_gutter(0.075.inches());
 
-    save_one_page_document(doc_title, output_filename, &page_bounds, render_projects)
+    save_document(doc_title, args.output, &page_setup, render_projects)
 }
 
 fn fill_project_into_rect(rect: WRect, instructions: &mut Instructions) {
@@ -63,21 +71,13 @@ fn fill_project_into_rect(rect: WRect, instructions: &mut Instructions) {
         .stroke(),
     );
 
-    instructions.set_stroke_color(Colors::gray(0.75));
     let inner_rect = rect.inset_all_q1(0.125.inches(), 0.25.inches(), 0.125.inches(), 0.0.inches());
-    fill_box_with_lines(&inner_rect, 0.25.inches(), 0.195.inches(), instructions);
-}
-
-/// Draw horizontal lines starting at 'offset' from the top of the box and at every 'gap'
-/// after it. Fill the box with these lines.
-///
-/// Assumes the box is in Q1.
-fn fill_box_with_lines(boxx: &WRect, offset: Unit, gap: Unit, instructions: &mut Instructions) {
-    let mut curr_y = boxx.top() - offset;
-
-    while curr_y > boxx.bottom_q1() {
-        let line = WLine::line(boxx.left(), curr_y, boxx.right(), curr_y);
-        instructions.push_shape(line.stroke());
-        curr_y = curr_y - gap;
-    }
+    RuledPaper::new(RuleHeight::Custom(0.195.inches()))
+        .with_first_line(0.25.inches())
+        .with_line_attributes(
+            Attributes::default()
+                .with_stroke_color(&Colors::gray(0.75))
+                .with_stroke_width(1.0),
+        )
+        .render(&inner_rect, instructions);
 }
@@ -0,0 +1,810 @@
+use argh::FromArgs;
+use chrono::{Datelike, NaiveDate};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use weekly::{DateSpan, Result, WeeklyError};
+
+#[derive(FromArgs)]
+/// Works with all of the sheets at once.
+struct Args {
+    #[argh(subcommand)]
+    command: SheetsCommand,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum SheetsCommand {
+    List(ListArgs),
+    Serve(ServeArgs),
+    Build(BuildArgs),
+}
+
+#[derive(FromArgs)]
+/// Lists the sheets and the parameters that they take.
+#[argh(subcommand, name = "list")]
+struct ListArgs {}
+
+#[derive(FromArgs)]
+/// Serves previews of the sheets on localhost, rendering them again for every request.
+#[argh(subcommand, name = "serve")]
+struct ServeArgs {
+    /// port to listen on (default: 8080)
+    #[argh(option, default = "8080")]
+    port: u16,
+}
+
+// The manifest has a section for each sheet to generate, named for the sheet, with its
+// parameters and output filename. The output can use {sheet} and {date:<strftime format>}, with
+// the sheet's date parameter or today. Relative paths are relative to the manifest.
+//
+//     [daily]
+//     date = 2026-11-01
+//     tasks = alice.txt
+//     output = alice/daily-{date:%Y-%m}.pdf
+#[derive(FromArgs)]
+/// Generates every sheet in a manifest, with a summary of what was written.
+#[argh(subcommand, name = "build")]
+struct BuildArgs {
+    /// the manifest
+    #[argh(positional)]
+    manifest: PathBuf,
+
+    /// number of sheets to generate at the same time (default: 1)
+    #[argh(option, short = 'j', default = "1")]
+    jobs: usize,
+}
+
+// Parameters for a sheet, like the query string "date=2026-10-01&form=yes". Each sheet takes the
+// ones it understands, and anything left over is an error.
+#[derive(Debug, Default)]
+struct Params(BTreeMap<String, String>);
+
+impl Params {
+    fn from_query(query: &str) -> std::result::Result<Params, String> {
+        let mut params = Params::default();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            params
+                .0
+                .insert(percent_decode(key)?, percent_decode(value)?);
+        }
+        Ok(params)
+    }
+
+    fn insert(&mut self, key: &str, value: &str) -> std::result::Result<(), String> {
+        match self.0.insert(key.to_string(), value.to_string()) {
+            Some(_) => Err(format!("{} is set twice", key)),
+            None => Ok(()),
+        }
+    }
+
+    fn take(&mut self, key: &str) -> Option<String> {
+        self.0.remove(key)
+    }
+
+    // Any date expression that the sheets understand, like 2026-10 or next-month. It is passed on
+    // as the dates that it means today, so that the sheet and its filename agree.
+    fn take_date(&mut self) -> std::result::Result<Option<DateSpan>, String> {
+        self.take("date")
+            .map(|date| date.parse().map_err(|err: WeeklyError| err.to_string()))
+            .transpose()
+    }
+
+    // True if the parameter is present, unless it is "no", "false" or "0".
+    fn take_flag(&mut self, key: &str) -> bool {
+        matches!(self.take(key), Some(value) if !matches!(value.as_str(), "no" | "false" | "0"))
+    }
+
+    fn finish(self, sheet: &Sheet) -> std::result::Result<(), String> {
+        match self.0.keys().next() {
+            Some(key) => Err(format!(
+                "{} doesn't take a \"{}\" parameter. It takes: {}",
+                sheet.name, key, sheet.params
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+// One of the binaries that prints a sheet, and how to turn parameters into its arguments.
+struct Sheet {
+    name: &'static str,
+    description: &'static str,
+    params: &'static str,
+    // Builds the command line from the parameters, writing the PDF to `output`.
+    args: fn(&mut Params, &str) -> std::result::Result<Vec<String>, String>,
+}
+
+const SHEETS: &[Sheet] = &[
+    Sheet {
+        name: "active",
+        description: "A big sheet of checkboxes for a to do list",
+        params: "form",
+        args: |params, output| {
+            let mut args = vec!["-o".to_string(), output.to_string()];
+            if params.take_flag("form") {
+                args.push("--form".to_string());
+            }
+            Ok(args)
+        },
+    },
+    Sheet {
+        name: "calendar",
+        description: "Month, quarter and year calendars",
+        params: "view (month, quarter, year, planner or digital), date, first-day",
+        args: |params, output| {
+            let mut args = vec!["-o".to_string(), output.to_string()];
+            if let Some(first_day) = params.take("first-day") {
+                args.extend(["--first-day".to_string(), first_day]);
+            }
+            let view = params.take("view").unwrap_or_else(|| "month".to_string());
+            let date = params.take_date()?;
+            match view.as_str() {
+                "month" | "quarter" => {
+                    args.push(view);
+                    if let Some(date) = date {
+                        args.extend(["--start".to_string(), date.to_string()]);
+                    }
+                }
+                "year" | "planner" | "digital" => {
+                    args.push(view);
+                    if let Some(date) = date {
+                        args.push(date.first().year().to_string());
+                    }
+                }
+                _ => return Err(format!("Unknown calendar view: {}", view)),
+            }
+            Ok(args)
+        },
+    },
+    Sheet {
+        name: "cornell",
+        description: "Cornell notes for the Remarkable 2",
+        params: "nothing",
+        args: |_, output| Ok(vec!["-o".to_string(), output.to_string()]),
+    },
+    Sheet {
+        name: "daily",
+        description: "A month of daily tasks to check off",
+        params: "date, form, tasks (a file)",
+        args: |params, output| {
+            let mut args = vec!["-o".to_string(), output.to_string()];
+            if let Some(date) = params.take_date()? {
+                args.push(date.to_string());
+            }
+            if params.take_flag("form") {
+                args.push("--form".to_string());
+            }
+            if let Some(tasks) = params.take("tasks") {
+                args.extend(["--tasks".to_string(), tasks]);
+            }
+            Ok(args)
+        },
+    },
+    Sheet {
+        name: "dayplanner",
+        description: "A daily planner page",
+        params: "date, first-day",
+        args: |params, output| {
+            let mut args = vec!["-o".to_string(), output.to_string()];
+            if let Some(date) = params.take_date()? {
+                args.extend(["-s".to_string(), date.to_string()]);
+            }
+            if let Some(first_day) = params.take("first-day") {
+                args.extend(["--first-day".to_string(), first_day]);
+            }
+            Ok(args)
+        },
+    },
+    Sheet {
+        name: "monthlies",
+        description: "A year of monthly tasks to check off",
+        params: "date, tasks (a file)",
+        args: |params, output| {
+            let mut args = vec!["-o".to_string(), output.to_string()];
+            if let Some(date) = params.take_date()? {
+                args.extend(["--start".to_string(), date.to_string()]);
+            }
+            if let Some(tasks) = params.take("tasks") {
+                args.extend(["--tasks".to_string(), tasks]);
+            }
+            Ok(args)
+        },
+    },
+    Sheet {
+        name: "patterns",
+        description: "Dots, squares, graph paper, isometric, hex, music staff and ruled paper",
+        params: "pattern (dots, squares, graph, isometric, hex, staff or ruled), size",
+        args: |params, output| {
+            let mut args = vec!["-o".to_string(), output.to_string()];
+            if let Some(size) = params.take("size") {
+                args.extend(["--paper".to_string(), size]);
+            }
+            let pattern = params.take("pattern").unwrap_or_else(|| "dots".to_string());
+            match pattern.as_str() {
+                "dots" | "squares" | "graph" | "isometric" | "hex" | "staff" | "ruled" => {
+                    args.push(pattern)
+                }
+                _ => return Err(format!("Unknown pattern: {}", pattern)),
+            }
+            Ok(args)
+        },
+    },
+    Sheet {
+        name: "projects",
+        description: "Four project cards on a half-letter page",
+        params: "nothing",
+        args: |_, output| Ok(vec!["-o".to_string(), output.to_string()]),
+    },
+    Sheet {
+        name: "weekly",
+        description: "A weekly productivity tracker",
+        params: "twice, flip",
+        args: |params, output| {
+            let mut args = vec!["-o".to_string(), output.to_string()];
+            if params.take_flag("twice") {
+                args.push("--twice".to_string());
+            }
+            if params.take_flag("flip") {
+                args.push("--flip".to_string());
+            }
+            Ok(args)
+        },
+    },
+];
+
+fn find_sheet(name: &str) -> Option<&'static Sheet> {
+    SHEETS.iter().find(|sheet| sheet.name == name)
+}
+
+// The sheets are separate binaries, built next to this one.
+fn sheet_binary(sheet: &Sheet) -> Result<PathBuf> {
+    let exe = std::env::current_exe()?;
+    Ok(exe.with_file_name(format!("{}{}", sheet.name, std::env::consts::EXE_SUFFIX)))
+}
+
+// The arguments for the sheet's binary, or an error if any of the parameters are wrong.
+fn sheet_args(
+    sheet: &Sheet,
+    mut params: Params,
+    output: &str,
+) -> std::result::Result<Vec<String>, String> {
+    let args = (sheet.args)(&mut params, output)?;
+    params.finish(sheet)?;
+    Ok(args)
+}
+
+// Runs the sheet's binary in `dir`, and returns what it writes to stdout.
+fn render_sheet(
+    sheet: &Sheet,
+    args: &[String],
+    dir: &Path,
+) -> std::result::Result<Vec<u8>, String> {
+    let binary = sheet_binary(sheet).map_err(|err| err.to_string())?;
+    let output = Command::new(&binary)
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|err| format!("Couldn't run {}: {}", binary.display(), err))?;
+    if !output.status.success() {
+        Err(String::from_utf8_lossy(&output.stderr).into_owned())
+    } else if !output.stdout.starts_with(b"%PDF") {
+        // Like the usage text, if the arguments asked for that instead.
+        Err(format!("{} didn't write a PDF", sheet.name))
+    } else {
+        Ok(output.stdout)
+    }
+}
+
+fn percent_decode(s: &str) -> std::result::Result<String, String> {
+    let bytes = s.as_bytes();
+    let mut decoded = vec![];
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'+' => decoded.push(b' '),
+            b'%' => {
+                let hex = s
+                    .get(index + 1..index + 3)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .ok_or_else(|| format!("Bad escape in {}", s))?;
+                decoded.push(hex);
+                index += 2;
+            }
+            byte => decoded.push(byte),
+        }
+        index += 1;
+    }
+    String::from_utf8(decoded).map_err(|_| format!("Not UTF-8: {}", s))
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn index_page() -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html><head><title>Sheets</title></head><body>\n<h1>Sheets</h1>\n<ul>\n",
+    );
+    for sheet in SHEETS {
+        html.push_str(&format!(
+            "<li><a href=\"/{0}\">{0}</a>: {1}. Parameters: {2}.</li>\n",
+            sheet.name,
+            html_escape(sheet.description),
+            html_escape(sheet.params)
+        ));
+    }
+    html.push_str(
+        "</ul>\n<p>Add parameters to the query string, like \
+         <a href=\"/calendar?view=quarter&amp;date=2027-01-01\">/calendar?view=quarter&amp;date=2027-01-01</a>. \
+         Every sheet also takes format (pdf, svg or png) and, for images, page, like \
+         <a href=\"/daily?format=png&amp;page=2\">/daily?format=png&amp;page=2</a>.</p>\n\
+         </body></html>\n",
+    );
+    html
+}
+
+fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &[u8]) -> Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    stream.flush()?;
+    Ok(())
+}
+
+fn respond_text(stream: &mut TcpStream, status: &str, text: &str) -> Result<()> {
+    respond(stream, status, "text/plain; charset=utf-8", text.as_bytes())
+}
+
+// What the server sends back. SVG and PNG show a single page.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Pdf,
+    Svg,
+    Png,
+}
+
+impl Format {
+    fn from_param(format: Option<String>) -> std::result::Result<Format, String> {
+        match format.as_deref() {
+            None | Some("pdf") => Ok(Format::Pdf),
+            Some("svg") => Ok(Format::Svg),
+            Some("png") => Ok(Format::Png),
+            Some(format) => Err(format!("Unknown format: {}. Use pdf, svg or png.", format)),
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Format::Pdf => "application/pdf",
+            Format::Svg => "image/svg+xml",
+            Format::Png => "image/png",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Pdf => "pdf",
+            Format::Svg => "svg",
+            Format::Png => "png",
+        }
+    }
+}
+
+// The page to show as an image, counting from 1.
+fn take_page(params: &mut Params) -> std::result::Result<u32, String> {
+    match params.take("page") {
+        None => Ok(1),
+        Some(page) => page
+            .parse()
+            .ok()
+            .filter(|page| *page > 0)
+            .ok_or_else(|| format!("Pages are numbered from 1, not {}", page)),
+    }
+}
+
+fn strings(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+// The programs that can turn a page of a PDF into an image, and their arguments for writing
+// `page` of `input` to stdout. The first one that is installed is used.
+fn converters(format: Format, input: &str, page: u32) -> Vec<(&'static str, Vec<String>)> {
+    let page = page.to_string();
+    let pages = ["-f", &page, "-l", &page];
+    match format {
+        Format::Pdf => vec![],
+        Format::Svg => vec![
+            (
+                "pdftocairo",
+                strings(&[&["-svg"], &pages[..], &[input, "-"]].concat()),
+            ),
+            (
+                "mutool",
+                strings(&["draw", "-q", "-F", "svg", "-o", "-", input, &page]),
+            ),
+        ],
+        Format::Png => vec![
+            (
+                "pdftocairo",
+                strings(&[&["-png", "-singlefile"], &pages[..], &[input, "-"]].concat()),
+            ),
+            (
+                "mutool",
+                strings(&["draw", "-q", "-F", "png", "-o", "-", input, &page]),
+            ),
+            (
+                "gs",
+                strings(&[
+                    "-q",
+                    "-dSAFER",
+                    "-dBATCH",
+                    "-dNOPAUSE",
+                    "-sDEVICE=png16m",
+                    "-r96",
+                    &format!("-dFirstPage={}", page),
+                    &format!("-dLastPage={}", page),
+                    "-sOutputFile=-",
+                    input,
+                ]),
+            ),
+        ],
+    }
+}
+
+// Turns the PDF into `format`, with a converter from `converters()`.
+fn convert(pdf: Vec<u8>, format: Format, page: u32) -> std::result::Result<Vec<u8>, String> {
+    if format == Format::Pdf {
+        return Ok(pdf);
+    }
+
+    // Not every converter reads from stdin, so they all get a file.
+    static NEXT_FILE: AtomicUsize = AtomicUsize::new(0);
+    let input = std::env::temp_dir().join(format!(
+        "sheets-{}-{}.pdf",
+        std::process::id(),
+        NEXT_FILE.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&input, &pdf).map_err(|err| err.to_string())?;
+    let result = run_converter(format, &input.to_string_lossy(), page);
+    let _ = std::fs::remove_file(&input);
+    result
+}
+
+fn run_converter(format: Format, input: &str, page: u32) -> std::result::Result<Vec<u8>, String> {
+    let converters = converters(format, input, page);
+    for (program, args) in &converters {
+        match Command::new(program).args(args).output() {
+            Ok(output) if output.status.success() && !output.stdout.is_empty() => {
+                return Ok(output.stdout)
+            }
+            Ok(output) => {
+                return Err(format!(
+                    "{} couldn't convert page {} to {}: {}",
+                    program,
+                    page,
+                    format.extension(),
+                    String::from_utf8_lossy(&output.stderr)
+                ))
+            }
+            // Not installed, so try the next one.
+            Err(_) => {}
+        }
+    }
+    let programs: Vec<&str> = converters.iter().map(|(program, _)| *program).collect();
+    Err(format!(
+        "Showing a sheet as {} needs one of these programs, and none of them is installed: {}",
+        format.extension(),
+        programs.join(", ")
+    ))
+}
+
+// Files named in a request have to be in the directory that the server was started in, so that
+// a request can't read anything else on the machine.
+fn check_served_file(path: &str) -> std::result::Result<(), String> {
+    let dir = std::env::current_dir().and_then(|dir| dir.canonicalize());
+    let file = Path::new(path).canonicalize();
+    match (dir, file) {
+        (Ok(dir), Ok(file)) if file.starts_with(&dir) => Ok(()),
+        _ => Err(format!(
+            "{} isn't a file in the directory that the server is serving.",
+            path
+        )),
+    }
+}
+
+fn handle_request(mut stream: TcpStream) -> Result<()> {
+    let mut request_line = String::new();
+    let mut reader = BufReader::new(&stream);
+    reader.read_line(&mut request_line)?;
+    // Skip the headers, which we don't need.
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    eprintln!("{} {}", method, target);
+    if method != "GET" {
+        return respond_text(
+            &mut stream,
+            "405 Method Not Allowed",
+            "Only GET is supported.",
+        );
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    if path == "/" {
+        return respond(
+            &mut stream,
+            "200 OK",
+            "text/html; charset=utf-8",
+            index_page().as_bytes(),
+        );
+    }
+
+    let sheet = match find_sheet(path.trim_start_matches('/')) {
+        Some(sheet) => sheet,
+        None => return respond_text(&mut stream, "404 Not Found", "No such sheet."),
+    };
+    let mut params = match Params::from_query(query) {
+        Ok(params) => params,
+        Err(message) => return respond_text(&mut stream, "400 Bad Request", &message),
+    };
+    let (format, page) = match (
+        Format::from_param(params.take("format")),
+        take_page(&mut params),
+    ) {
+        (Ok(format), Ok(page)) => (format, page),
+        (Err(message), _) | (_, Err(message)) => {
+            return respond_text(&mut stream, "400 Bad Request", &message)
+        }
+    };
+
+    if let Some(tasks) = params.0.get("tasks") {
+        if let Err(message) = check_served_file(tasks) {
+            return respond_text(&mut stream, "403 Forbidden", &message);
+        }
+    }
+
+    let args = match sheet_args(sheet, params, "-") {
+        Ok(args) => args,
+        Err(message) => return respond_text(&mut stream, "400 Bad Request", &message),
+    };
+    match render_sheet(sheet, &args, Path::new(".")).and_then(|pdf| convert(pdf, format, page)) {
+        Ok(body) => respond(&mut stream, "200 OK", format.content_type(), &body),
+        Err(message) => respond_text(&mut stream, "500 Internal Server Error", &message),
+    }
+}
+
+fn serve(args: &ServeArgs) -> Result<()> {
+    // Only this machine can connect.
+    let listener = TcpListener::bind(("127.0.0.1", args.port))?;
+    eprintln!("Serving sheets at http://localhost:{}/", args.port);
+    for stream in listener.incoming() {
+        // A bad request shouldn't stop the server.
+        if let Err(err) = stream.map_err(Into::into).and_then(handle_request) {
+            eprintln!("Error: {}", err);
+        }
+    }
+    Ok(())
+}
+
+// One sheet to generate from a manifest.
+struct Job {
+    sheet: &'static Sheet,
+    output: String,
+    args: Vec<String>,
+}
+
+// Fills in {sheet}, {date} and {date:<format>} in an output filename.
+fn expand_output(
+    template: &str,
+    sheet: &Sheet,
+    date: &NaiveDate,
+) -> std::result::Result<String, String> {
+    let mut output = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("Missing }} in {}", template))?
+            + start;
+        let field = &rest[start + 1..end];
+        match field.split_once(':') {
+            _ if field == "sheet" => output.push_str(sheet.name),
+            _ if field == "date" => output.push_str(&date.to_string()),
+            Some(("date", format)) => write!(output, "{}", date.format(format))
+                .map_err(|_| format!("Bad date format: {}", format))?,
+            _ => return Err(format!("Unknown field {{{}}} in {}", field, template)),
+        }
+        rest = &rest[end + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+fn make_job(
+    sheet: &'static Sheet,
+    mut params: Params,
+    output: Option<String>,
+) -> std::result::Result<Job, String> {
+    let date = match params.0.get("date") {
+        Some(date) => date
+            .parse::<DateSpan>()
+            .map_err(|err| err.to_string())?
+            .first(),
+        None => weekly::today(),
+    };
+    let template = output.unwrap_or_else(|| "{sheet}-{date:%Y-%m}.pdf".to_string());
+    let output = expand_output(&template, sheet, &date)?;
+    if output == "-" {
+        return Err("Sheets in a manifest can't be written to stdout".to_string());
+    }
+    let args = sheet_args(sheet, std::mem::take(&mut params), &output)?;
+    Ok(Job {
+        sheet,
+        output,
+        args,
+    })
+}
+
+fn parse_manifest(text: &str) -> std::result::Result<Vec<Job>, String> {
+    let mut jobs = vec![];
+    // The sheet, the line it starts on, its parameters and its output.
+    let mut current: Option<(&'static Sheet, usize, Params, Option<String>)> = None;
+    let finish = |(sheet, line, params, output)| {
+        make_job(sheet, params, output).map_err(|err| format!("line {}: {}", line, err))
+    };
+    for (index, line) in text.lines().enumerate() {
+        let line_error = |err: String| format!("line {}: {}", index + 1, err);
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            if let Some(section) = current.take() {
+                jobs.push(finish(section)?);
+            }
+            let sheet = find_sheet(name.trim())
+                .ok_or_else(|| line_error(format!("No such sheet: {}", name)))?;
+            current = Some((sheet, index + 1, Params::default(), None));
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| line_error("Expected [sheet] or key = value".to_string()))?;
+        let (key, value) = (key.trim(), value.trim());
+        match &mut current {
+            None => return Err(line_error("Parameters must follow a [sheet]".to_string())),
+            Some((_, _, _, output)) if key == "output" => *output = Some(value.to_string()),
+            Some((_, _, params, _)) => params.insert(key, value).map_err(line_error)?,
+        }
+    }
+    if let Some(section) = current {
+        jobs.push(finish(section)?);
+    }
+    Ok(jobs)
+}
+
+// Runs the job, and returns the size of the file it wrote.
+fn run_job(job: &Job, dir: &Path) -> std::result::Result<u64, String> {
+    if let Some(parent) = dir.join(&job.output).parent() {
+        std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    // Whole seconds, since some filesystems don't keep anything finer.
+    let started = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(UNIX_EPOCH, |since| {
+            UNIX_EPOCH + Duration::from_secs(since.as_secs())
+        });
+    render_sheet(job.sheet, &job.args, dir)?;
+    // A sheet that succeeds should have written the file, but don't report a directory, or a file
+    // left over from an earlier build, as written.
+    match dir.join(&job.output).metadata() {
+        Ok(metadata)
+            if metadata.is_file()
+                && metadata
+                    .modified()
+                    .is_ok_and(|modified| modified >= started) =>
+        {
+            Ok(metadata.len())
+        }
+        _ => Err("Nothing was written".to_string()),
+    }
+}
+
+fn build(args: &BuildArgs) -> Result<()> {
+    let text = std::fs::read_to_string(&args.manifest)?;
+    let jobs = parse_manifest(&text)
+        .map_err(|err| WeeklyError::ConfigError(format!("{}, {}", args.manifest.display(), err)))?;
+    let dir = match args.manifest.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    // Each worker takes the next job until there are none left. The sheets are separate
+    // processes, so the workers spend their time waiting for them.
+    let start = Instant::now();
+    let next_job = AtomicUsize::new(0);
+    let results: Vec<Mutex<Option<std::result::Result<u64, String>>>> =
+        jobs.iter().map(|_| Mutex::new(None)).collect();
+    thread::scope(|scope| {
+        for _ in 0..args.jobs.clamp(1, jobs.len().max(1)) {
+            scope.spawn(|| loop {
+                let index = next_job.fetch_add(1, Ordering::Relaxed);
+                match jobs.get(index) {
+                    // unwrap: a worker only panics if another one did.
+                    Some(job) => *results[index].lock().unwrap() = Some(run_job(job, &dir)),
+                    None => break,
+                }
+            });
+        }
+    });
+
+    let mut failures = 0;
+    for (job, result) in jobs.iter().zip(results) {
+        // unwrap: every job has been run.
+        match result.into_inner().unwrap().unwrap() {
+            Ok(size) => println!("wrote  {} ({} KB)", job.output, size.div_ceil(1024)),
+            Err(err) => {
+                failures += 1;
+                println!("FAILED {} ({}): {}", job.output, job.sheet.name, err.trim());
+            }
+        }
+    }
+    println!(
+        "Wrote {} of {} sheets in {:.1}s.",
+        jobs.len() - failures,
+        jobs.len(),
+        start.elapsed().as_secs_f64()
+    );
+    if failures > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn list() {
+    for sheet in SHEETS {
+        println!("{:12}{}", sheet.name, sheet.description);
+        println!("{:12}parameters: {}", "", sheet.params);
+    }
+}
+
+fn main() {
+    let args: Args = argh::from_env();
+    let result = match &args.command {
+        SheetsCommand::List(_) => {
+            list();
+            Ok(())
+        }
+        SheetsCommand::Serve(serve_args) => serve(serve_args),
+        SheetsCommand::Build(build_args) => build(build_args),
+    };
+    if let Err(err) = result {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
@@ -1,9 +1,12 @@
 use argh::FromArgs;
 use printpdf::PdfDocumentReference;
+use std::path::PathBuf;
+use weekly::cells::{Bullet, CellAlignment, CellRenderer};
+use weekly::patterns::{DotGrid, Pattern};
 use weekly::{
     save_double_sided_document, save_one_page_document, sizes, Attributes, Circle, Colors,
-    GridDescription, HasRenderAttrs, Instructions, NumericUnit, Result, TGrid, TextContext, Unit,
-    WLine, WRect,
+    GridDescription, HasRenderAttrs, Instructions, Layout, NumericUnit, Result, TGrid, TextContext,
+    Unit, WRect,
 };
 
 const GOLDEN_RATIO: f64 = 1.618033988749894;
@@ -28,9 +31,13 @@ const TOTAL_TOP_LINES: f64 = 9.0;
 #[derive(Debug, FromArgs)]
 /// Generates a weekly productivity tracker.
 struct Args {
-    /// name of the output file
-    #[argh(positional, default = "String::from(\"weekly.pdf\")")]
-    output_filename: String,
+    /// output filename, or - for stdout (default: weekly.pdf)
+    #[argh(option, short = 'o')]
+    output: Option<PathBuf>,
+
+    /// output filename, from before --output (use that instead)
+    #[argh(positional)]
+    output_filename: Option<PathBuf>,
 
     /// print twice on two pages
     #[argh(switch, short = '2')]
@@ -53,6 +60,7 @@ where
 
     render_func: F,
     offset: Unit,
+    day_grid: bool,
 }
 
 impl<F: Fn(&WRect, usize, &mut Instructions)> SimpleDescription<F> {
@@ -73,6 +81,7 @@ impl<F: Fn(&WRect, usize, &mut Instructions)> SimpleDescription<F> {
             text_context: text_context.clone(),
             render_func,
             offset: 1.0.mm(),
+            day_grid: false,
         }
     }
 
@@ -80,6 +89,12 @@ impl<F: Fn(&WRect, usize, &mut Instructions)> SimpleDescription<F> {
         self.offset = offset;
         self
     }
+
+    // Nest a grid of boxes for the days of the week at the right end of every row.
+    fn with_day_grid(mut self) -> Self {
+        self.day_grid = true;
+        self
+    }
 }
 
 impl<F: Fn(&WRect, usize, &mut Instructions)> GridDescription for SimpleDescription<F> {
@@ -136,6 +151,105 @@ impl<F: Fn(&WRect, usize, &mut Instructions)> GridDescription for SimpleDescript
 
         instructions.pop_state();
     }
+
+    fn cell_grid(
+        &self,
+        row: usize,
+        _col: usize,
+        cell_rect: &WRect,
+    ) -> Option<Box<dyn GridDescription + '_>> {
+        if self.day_grid {
+            Some(Box::new(DayGridDescription {
+                cell_rect: cell_rect.clone(),
+                is_header: row == 0,
+                text_context: &self.text_context,
+            }))
+        } else {
+            None
+        }
+    }
+}
+
+// One row of square boxes, one for each day of the week, right-aligned in a row of the
+// habit tracker. In the header row, the boxes are labeled with the days instead.
+struct DayGridDescription<'a> {
+    cell_rect: WRect,
+    is_header: bool,
+    text_context: &'a TextContext,
+}
+
+impl GridDescription for DayGridDescription<'_> {
+    fn num_rows(&self) -> Option<usize> {
+        Some(1)
+    }
+
+    fn num_cols(&self) -> Option<usize> {
+        Some(DAY_LETTERS.len())
+    }
+
+    fn col_width(&self) -> Option<Unit> {
+        Some(self.cell_rect.height())
+    }
+
+    // The (unlabeled) row label pushes the day boxes to the right end of the row.
+    fn row_label_width(&self) -> Option<Unit> {
+        Some(self.cell_rect.width() - self.cell_rect.height() * DAY_LETTERS.len() as f64)
+    }
+
+    fn horiz_line_style(&self, _index: usize, _num_rows: usize) -> Option<Attributes> {
+        None
+    }
+
+    fn vert_line_style(&self, index: usize, num_cols: usize) -> Option<Attributes> {
+        // The last line is the border of the table.
+        if self.is_header || index == num_cols {
+            None
+        } else {
+            Some(
+                Attributes::default()
+                    .with_stroke_color(&Colors::gray(0.75))
+                    .with_dash(1, 1),
+            )
+        }
+    }
+
+    fn render_cell_contents(
+        &self,
+        _row: usize,
+        col: usize,
+        cell_rect: &WRect,
+        instructions: &mut Instructions,
+    ) {
+        if self.is_header {
+            instructions.push_state();
+            instructions.set_fill_color(Colors::white());
+            self.text_context
+                .with_text_height((cell_rect.height() - 1.0.mm()) * 1.9)
+                .bold(true)
+                .render(
+                    DAY_LETTERS[col],
+                    cell_rect.left() + 1.4.mm(),
+                    cell_rect.bottom_q1() + 1.5.mm(),
+                    instructions,
+                );
+            instructions.pop_state();
+        }
+    }
+}
+
+fn table_rect(rect: &WRect) -> WRect {
+    let line_space = rect.height() / TOTAL_TOP_LINES;
+    rect.resize(rect.width(), rect.height() - line_space)
+}
+
+fn render_table<F: Fn(&WRect, usize, &mut Instructions)>(
+    description: SimpleDescription<F>,
+    instructions: &mut Instructions,
+) {
+    instructions.push_shape(description.rect.clone().stroke());
+
+    let tgrid = TGrid::with_description(description);
+    tgrid.append_to_instructions(instructions);
 }
 
 fn render_lines<T: AsRef<str>, F: Fn(&WRect, usize, &mut Instructions)>(
@@ -147,32 +261,15 @@ fn render_lines<T: AsRef<str>, F: Fn(&WRect, usize, &mut Instructions)>(
     render_func: F,
     instructions: &mut Instructions,
 ) {
-    let line_space = rect.height() / TOTAL_TOP_LINES;
-
-    let table_rect = rect.resize(rect.width(), rect.height() - line_space);
-
     let description = SimpleDescription::new(
-        &table_rect,
+        &table_rect(rect),
         num_rows,
         text.as_ref(),
         text_context,
         render_func,
     )
     .set_offset(offset);
-    let tgrid = TGrid::with_description(description);
-
-    instructions.push_shape(table_rect.stroke());
-
-    tgrid.append_to_instructions(instructions);
-}
-
-fn render_left_circle(rect: &WRect, instructions: &mut Instructions) {
-    let radius = rect.height() / 2.0;
-    let x = rect.left() + radius;
-    let y = rect.bottom_q1() + radius;
-
-    let circle = Circle::at_zero(radius - 1.15.mm()).move_to(x, y);
-    instructions.push_shape(circle);
+    render_table(description, instructions);
 }
 
 fn render_days(rect: &WRect, text_context: &TextContext, instructions: &mut Instructions) {
@@ -225,35 +322,30 @@ fn render_weekly(
         page_rect.inset_all_q1(0.25.inches(), 0.25.inches(), 0.25.inches(), 0.25.inches());
 
     // Make the top and bottom halves in the golden ratio.
-    // So, x + Phi.x = page height
-    let top_height = print_rect.height() / (1.0 + GOLDEN_RATIO);
-    let bottom_height = print_rect.height() - top_height;
-
-    // Grid is based around a 5-column grid.
-    let grid_x = print_rect.width() / 5.0;
+    let halves = Layout::rows()
+        .flex("top", 1.0)
+        .flex("calendar", GOLDEN_RATIO)
+        .split(&print_rect);
+
+    // Top half is based around a 5-column grid.
+    let top = Layout::columns()
+        .flex("priorities", 2.0)
+        .flex("tracker", 2.0)
+        .flex("weekend", 1.0)
+        .split(&halves["top"]);
 
     let top_text_offset = 5.0.mm();
 
-    let priorities_rect = print_rect.resize(grid_x * 2.0, top_height);
     render_priorities(
-        &priorities_rect,
+        &top["priorities"],
         top_text_offset,
         text_context,
         instructions,
     );
+    render_tracker(&top["tracker"], top_text_offset, text_context, instructions);
+    render_weekend(&top["weekend"], top_text_offset, text_context, instructions);
 
-    let tracker_rect = priorities_rect.move_by(grid_x * 2.0, Unit::zero());
-    render_tracker(&tracker_rect, top_text_offset, text_context, instructions);
-
-    let weekend_rect = tracker_rect
-        .move_by(grid_x * 2.0, Unit::zero())
-        .resize(grid_x, priorities_rect.height());
-    render_weekend(&weekend_rect, top_text_offset, text_context, instructions);
-
-    let calendar_rect = print_rect
-        .resize(print_rect.width(), bottom_height)
-        .move_by(Unit::zero(), -top_height);
-    render_days(&calendar_rect, text_context, instructions);
+    render_days(&halves["calendar"], text_context, instructions);
 }
 
 fn render_weekend(
@@ -279,45 +371,14 @@ fn render_tracker(
     text_context: &TextContext,
     instructions: &mut Instructions,
 ) {
-    render_lines(
-        tracker_rect,
+    let description = SimpleDescription::new(
+        &table_rect(tracker_rect),
+        8,
         "Habit Tracker",
         text_context,
-        8,
-        top_text_offset,
         |rect, row, instructions| {
-            let text_context = text_context.with_text_height((rect.height() - 1.0.mm()) * 1.9);
-
-            let small_grid_left = rect.right() - rect.height() * 7.0;
-            if row > 0 {
-                instructions.push_state();
-                instructions.set_stroke_color(Colors::gray(0.75));
-                instructions.set_dash(1, 1);
-                for i in 0..7 {
-                    let l = small_grid_left + rect.height() * i;
-                    let wline = WLine::line(l, rect.bottom_q1(), l, rect.top());
-                    instructions.push_shape(wline.stroke())
-                }
-                instructions.pop_state();
-            } else {
-                // Top row labels
-                instructions.push_state();
-                instructions.set_fill_color(Colors::white());
-                let bold_context = text_context.bold(true);
-                for (i, letter) in DAY_LETTERS.iter().enumerate() {
-                    let l = small_grid_left + rect.height() * i as f64;
-                    bold_context.render(
-                        letter,
-                        l + 1.4.mm(),
-                        rect.bottom_q1() + 1.5.mm(),
-                        instructions,
-                    );
-                }
-
-                instructions.pop_state();
-            }
-
             if row > 0 && row < HABITS.len() + 1 {
+                let text_context = text_context.with_text_height((rect.height() - 1.0.mm()) * 1.9);
                 instructions.push_state();
                 instructions.set_fill_color(Colors::black());
                 text_context.render(
@@ -329,8 +390,10 @@ fn render_tracker(
                 instructions.pop_state();
             }
         },
-        instructions,
-    );
+    )
+    .set_offset(top_text_offset)
+    .with_day_grid();
+    render_table(description, instructions);
 }
 
 fn render_priorities(
@@ -347,7 +410,9 @@ fn render_priorities(
         top_text_offset,
         |rect, row, instructions| {
             if row > 0 {
-                render_left_circle(rect, instructions)
+                Bullet::new(rect.height() / 2.0 - 1.15.mm())
+                    .with_alignment(CellAlignment::Left)
+                    .render(rect, instructions)
             }
         },
         instructions,
@@ -365,33 +430,28 @@ fn render_dotted(_: &PdfDocumentReference, dotted_rect: &WRect, instructions: &m
     rounded_rect.set_corner_radius(2.0.mm());
     instructions.push_shape(rounded_rect.stroke());
 
-    instructions.set_fill_color(Colors::gray(0.7));
+    // Keep the dots a full grid space away from the border.
     let grid_spacing = 0.25.inches();
-
-    let base_circle = Circle::at_zero(0.25.mm());
-    let mut x = dotted_rect.left() + grid_spacing;
-    while x <= dotted_rect.right() - grid_spacing {
-        let mut y = dotted_rect.top() - grid_spacing;
-
-        while y >= dotted_rect.bottom_q1() + grid_spacing {
-            instructions.push_shape(base_circle.move_to(x, y).fill());
-
-            y = y - grid_spacing;
-        }
-        x = x + grid_spacing;
-    }
+    DotGrid::new(grid_spacing)
+        .with_attributes(Attributes::default().with_fill_color(&Colors::gray(0.7)))
+        .render(
+            &dotted_rect.inset_q1(grid_spacing, grid_spacing),
+            instructions,
+        );
     instructions.pop_state();
 }
 
 fn render_weekly_page(doc: &PdfDocumentReference, page_rect: &WRect) -> Result<Instructions> {
     let mut instructions = Instructions::default();
-    let top_half = page_rect.resize(page_rect.width(), page_rect.height() / 2.0);
+    let halves = Layout::rows()
+        .flex("top", 1.0)
+        .flex("bottom", 1.0)
+        .split(page_rect);
     let text_context = TextContext::helvetica();
-    render_weekly(doc, &top_half, &text_context, &mut instructions);
+    render_weekly(doc, &halves["top"], &text_context, &mut instructions);
 
-    let bottom_half = top_half
-        .move_by(Unit::zero(), -top_half.height())
-        .inset_all_q1(0.25.inches(), 0.25.inches(), 0.25.inches(), 0.25.inches());
+    let bottom_half =
+        halves["bottom"].inset_all_q1(0.25.inches(), 0.25.inches(), 0.25.inches(), 0.25.inches());
     render_dotted(doc, &bottom_half, &mut instructions);
 
     Ok(instructions)
@@ -401,11 +461,15 @@ pub fn main() -> Result<()> {
     let args: Args = argh::from_env();
 
     let page_rect = sizes::letter();
+    let output = args
+        .output
+        .or(args.output_filename)
+        .unwrap_or_else(|| PathBuf::from("weekly.pdf"));
 
     if args.twice {
         save_double_sided_document(
             "Productivity Tracker",
-            args.output_filename,
+            output,
             &page_rect,
             args.flip,
             render_weekly_page,
@@ -413,7 +477,7 @@ pub fn main() -> Result<()> {
     } else {
         save_one_page_document(
             "Productivity Tracker",
-            args.output_filename,
+            output,
             &page_rect,
             render_weekly_page,
         )
@@ -0,0 +1,344 @@
+//! Calendars of months, laid out as a grid of weeks by weekdays.
+
+use crate::cells::{CellAlignment, CellRenderer, DayNumber};
+use crate::{
+    Attributes, Colors, Datetools, FontProxy, GridDescription, Instructions, LinkTarget, TGrid,
+    TextContext, Unit, WRect,
+};
+use chrono::{Datelike, NaiveDate, Weekday};
+use printpdf::Color;
+
+// Heights of the title and weekday name bands, as a percentage of the calendar height.
+const TITLE_HEIGHT_PCT: f64 = 8.0;
+const WEEKDAY_HEIGHT_PCT: f64 = 4.0;
+
+/// A month at a glance: a title, a row of weekday names, and a row for each week with the day
+/// numbers.
+///
+/// The calendar fills whatever rect it is rendered into, so the same calendar works as a full
+/// page or as a small one in the corner of another sheet. Days from the previous and next months
+/// that fill out the first and last weeks are greyed.
+#[derive(Debug, Clone)]
+pub struct MonthCalendar {
+    month: NaiveDate,
+    first_weekday: Weekday,
+    title: Option<String>,
+    six_weeks: bool,
+    show_adjacent_days: bool,
+    text_context: TextContext,
+    day_alignment: CellAlignment,
+    day_height_pct: f64,
+    day_color: Color,
+    adjacent_day_color: Color,
+    line_attributes: Option<Attributes>,
+    day_links: bool,
+}
+
+impl MonthCalendar {
+    pub fn new(date: &impl Datelike) -> MonthCalendar {
+        let month = date.first_of_month();
+        MonthCalendar {
+            month,
+            first_weekday: Weekday::Sun,
+            title: Some(month.format("%B %Y").to_string()),
+            six_weeks: false,
+            show_adjacent_days: true,
+            text_context: TextContext::helvetica(),
+            day_alignment: CellAlignment::Left,
+            day_height_pct: 20.0,
+            day_color: Colors::black(),
+            adjacent_day_color: Colors::gray(0.6),
+            line_attributes: Some(Attributes::default().with_stroke_width(0.5)),
+            day_links: false,
+        }
+    }
+
+    pub fn with_first_weekday(mut self, first_weekday: Weekday) -> Self {
+        self.first_weekday = first_weekday;
+        self
+    }
+
+    // Defaults to the month and year, like "October 2026".
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn without_title(mut self) -> Self {
+        self.title = None;
+        self
+    }
+
+    // Always use six rows of weeks, so that calendars for different months are the same size.
+    pub fn with_six_weeks(mut self, six_weeks: bool) -> Self {
+        self.six_weeks = six_weeks;
+        self
+    }
+
+    // Whether to show the (greyed) days from the months before and after.
+    pub fn with_adjacent_days(mut self, show_adjacent_days: bool) -> Self {
+        self.show_adjacent_days = show_adjacent_days;
+        self
+    }
+
+    // The font for the title, weekday names and day numbers. The sizes are set by the calendar.
+    pub fn with_text_context(mut self, text_context: &TextContext) -> Self {
+        self.text_context = text_context.clone();
+        self
+    }
+
+    // Where the day numbers go in their cells, and how tall they are as a percentage of the cell.
+    // Small calendars look best with centered numbers about half of the cell height.
+    pub fn with_day_numbers(mut self, alignment: CellAlignment, height_pct: f64) -> Self {
+        self.day_alignment = alignment;
+        self.day_height_pct = height_pct;
+        self
+    }
+
+    pub fn with_day_colors(mut self, day_color: &Color, adjacent_day_color: &Color) -> Self {
+        self.day_color = day_color.clone();
+        self.adjacent_day_color = adjacent_day_color.clone();
+        self
+    }
+
+    // The lines between the days. None for no lines.
+    pub fn with_line_attributes(mut self, line_attributes: Option<Attributes>) -> Self {
+        self.line_attributes = line_attributes;
+        self
+    }
+
+    // Links each day of the month to the page anchored with its date, as yyyy-mm-dd.
+    pub fn with_day_links(mut self, day_links: bool) -> Self {
+        self.day_links = day_links;
+        self
+    }
+
+    pub fn month(&self) -> NaiveDate {
+        self.month
+    }
+
+    // The date in the top-left cell of the grid.
+    pub fn first_date(&self) -> NaiveDate {
+        self.month.start_of_week(self.first_weekday)
+    }
+
+    pub fn num_weeks(&self) -> usize {
+        if self.six_weeks {
+            return 6;
+        }
+        self.month.weeks_in_month(self.first_weekday).len()
+    }
+
+    // Every date shown in the grid, a week at a time.
+    pub fn dates(&self) -> Vec<NaiveDate> {
+        self.first_date().date_range(self.num_weeks() as i64 * 7)
+    }
+
+    pub fn weekdays(&self) -> Vec<Weekday> {
+        let mut weekday = self.first_weekday;
+        (0..7)
+            .map(|_| {
+                let this = weekday;
+                weekday = weekday.succ();
+                this
+            })
+            .collect()
+    }
+
+    // The rect of the week grid, without the title and weekday names, when rendered in `rect`.
+    pub fn grid_rect(&self, rect: &WRect) -> WRect {
+        let (_, _, grid_rect) = self.split(rect);
+        grid_rect
+    }
+
+    // The rect of a single day when rendered in `rect`, or None if it isn't in the grid.
+    pub fn day_rect(&self, rect: &WRect, date: &NaiveDate) -> Option<WRect> {
+        let index = date.signed_duration_since(self.first_date()).num_days();
+        if index < 0 || index >= self.num_weeks() as i64 * 7 {
+            return None;
+        }
+        let grid_rect = self.grid_rect(rect);
+        let width = grid_rect.width() / 7;
+        let height = grid_rect.height() / self.num_weeks() as f64;
+        Some(WRect::with_dimensions(width, height).move_to(
+            grid_rect.left() + width * (index % 7) as f64,
+            grid_rect.top() - height * (index / 7) as f64,
+        ))
+    }
+
+    // Splits the rect into the title band, the weekday band, and the week grid.
+    fn split(&self, rect: &WRect) -> (Option<WRect>, WRect, WRect) {
+        let title_height = if self.title.is_some() {
+            rect.height().pct(TITLE_HEIGHT_PCT)
+        } else {
+            Unit::zero()
+        };
+        let weekday_height = rect.height().pct(WEEKDAY_HEIGHT_PCT);
+
+        let title_rect = self.title.as_ref().map(|_| {
+            WRect::with_dimensions(rect.width(), title_height).move_to(rect.left(), rect.top())
+        });
+        let weekday_rect = WRect::with_dimensions(rect.width(), weekday_height)
+            .move_to(rect.left(), rect.top() - title_height);
+        let grid_rect = rect.inset_all_q1(
+            Unit::zero(),
+            title_height + weekday_height,
+            Unit::zero(),
+            Unit::zero(),
+        );
+        (title_rect, weekday_rect, grid_rect)
+    }
+
+    fn render_title(&self, title: &str, rect: &WRect, instructions: &mut Instructions) {
+        let font = self.text_context.font().bold(true);
+        let font_size = fitted_font_size(font, title, rect.width(), rect.height().pct(60.0));
+        let x = rect.left() + (rect.width() - font.text_width(title, font_size)) / 2;
+        let y = rect.bottom_q1() + (rect.height() - font.cap_height(font_size)) / 2;
+        instructions.push_text(title, font_size, x, y, font);
+    }
+
+    fn render_weekdays(&self, rect: &WRect, instructions: &mut Instructions) {
+        let font = self.text_context.font();
+        let weekdays = self.weekdays();
+        let width = rect.width() / 7;
+        let cap_height = rect.height().pct(50.0);
+        let names = weekday_names(font, &weekdays, width.pct(90.0), cap_height);
+        let font_size = names
+            .iter()
+            .map(|name| fitted_font_size(font, name, width.pct(90.0), cap_height))
+            .fold(f64::MAX, f64::min);
+
+        for (col, name) in names.iter().enumerate() {
+            let center = rect.left() + width * col as f64 + width / 2;
+            let x = center - font.text_width(name, font_size) / 2;
+            let y = rect.bottom_q1() + (rect.height() - font.cap_height(font_size)) / 2;
+            instructions.push_text(name, font_size, x, y, font);
+        }
+    }
+}
+
+impl CellRenderer for MonthCalendar {
+    fn render(&self, rect: &WRect, instructions: &mut Instructions) {
+        let (title_rect, weekday_rect, grid_rect) = self.split(rect);
+
+        instructions.push_state();
+        instructions.set_fill_color(self.day_color.clone());
+        if let (Some(title), Some(title_rect)) = (&self.title, &title_rect) {
+            self.render_title(title, title_rect, instructions);
+        }
+        self.render_weekdays(&weekday_rect, instructions);
+
+        let grid = MonthGrid {
+            calendar: self,
+            dates: self.dates(),
+        };
+        TGrid::nested_in(Box::new(grid), &grid_rect).append_to_instructions(instructions);
+        instructions.pop_state();
+    }
+}
+
+struct MonthGrid<'a> {
+    calendar: &'a MonthCalendar,
+    dates: Vec<NaiveDate>,
+}
+
+impl<'a> GridDescription for MonthGrid<'a> {
+    fn num_rows(&self) -> Option<usize> {
+        Some(self.calendar.num_weeks())
+    }
+
+    fn num_cols(&self) -> Option<usize> {
+        Some(7)
+    }
+
+    fn horiz_line_style(&self, _index: usize, _num_rows: usize) -> Option<Attributes> {
+        self.calendar.line_attributes.clone()
+    }
+
+    fn vert_line_style(&self, _index: usize, _num_cols: usize) -> Option<Attributes> {
+        self.calendar.line_attributes.clone()
+    }
+
+    fn cell_link(&self, row: usize, col: usize) -> Option<LinkTarget> {
+        let date = self.dates[row * 7 + col];
+        if self.calendar.day_links && date.month() == self.calendar.month.month() {
+            Some(LinkTarget::anchor(date.format("%Y-%m-%d").to_string()))
+        } else {
+            None
+        }
+    }
+
+    fn render_cell_contents(
+        &self,
+        row: usize,
+        col: usize,
+        cell_rect: &WRect,
+        instructions: &mut Instructions,
+    ) {
+        let date = self.dates[row * 7 + col];
+        let in_month = date.month() == self.calendar.month.month();
+        if !in_month && !self.calendar.show_adjacent_days {
+            return;
+        }
+        let color = if in_month {
+            &self.calendar.day_color
+        } else {
+            &self.calendar.adjacent_day_color
+        };
+        DayNumber::new(date.day())
+            .with_text_context(&self.calendar.text_context)
+            .with_alignment(self.calendar.day_alignment)
+            .with_height_pct(self.calendar.day_height_pct)
+            .with_color(color)
+            .render(cell_rect, instructions);
+    }
+
+    fn font(&self) -> FontProxy {
+        self.calendar.text_context.font()
+    }
+}
+
+// The font size that makes `text` `cap_height` tall, shrunk if needed to fit in `width`.
+fn fitted_font_size(font: FontProxy, text: &str, width: Unit, cap_height: Unit) -> f64 {
+    let font_size = cap_height / font.cap_height(1.0);
+    let text_width = font.text_width(text, font_size);
+    if text_width > width {
+        font_size * (width / text_width)
+    } else {
+        font_size
+    }
+}
+
+// The longest weekday names ("Monday", "Mon" or "M") that fit in `width` at full size.
+fn weekday_names(
+    font: FontProxy,
+    weekdays: &[Weekday],
+    width: Unit,
+    cap_height: Unit,
+) -> Vec<String> {
+    let font_size = cap_height / font.cap_height(1.0);
+    let full_names: Vec<String> = weekdays
+        .iter()
+        .map(|weekday| {
+            NaiveDate::from_isoywd(2000, 1, *weekday)
+                .format("%A")
+                .to_string()
+        })
+        .collect();
+    for len in &[None, Some(3)] {
+        let names: Vec<String> = full_names
+            .iter()
+            .map(|name| name[..len.unwrap_or_else(|| name.len())].to_string())
+            .collect();
+        if names
+            .iter()
+            .all(|name| font.text_width(name, font_size) <= width)
+        {
+            return names;
+        }
+    }
+    full_names
+        .iter()
+        .map(|name| name[..1].to_string())
+        .collect()
+}
@@ -0,0 +1,324 @@
+use crate::{today, Datetools, Result, WeeklyError};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+const YEARS: RangeInclusive<i32> = 1..=9999;
+
+/// A span of days, from `first()` to `last()` inclusive, for date arguments.
+///
+/// Parsed from a date expression, which is one of these, or a range of two of them like
+/// `2026-01..2026-03`:
+///
+/// * a day, month, ISO week, quarter or year: `2026-10-18`, `2026-10`, `2026-W42`, `2026-Q4`,
+///   `2026`
+/// * `today`, `yesterday` or `tomorrow`
+/// * the week, month, quarter or year around today: `this-week`, `next-month`, `last-quarter`,
+///   `this-year`. Weeks start on Monday, like ISO weeks.
+/// * a day relative to today, in days, weeks, months or years: `+3d`, `+2w`, `-1m`, `+1y`. A
+///   negative one can be the value of an option, like `-s -1m`, but as a positional argument it
+///   has to come after `--`, like `daily -- -1m`, so that it isn't taken for an option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateSpan {
+    first: NaiveDate,
+    last: NaiveDate,
+}
+
+impl DateSpan {
+    pub fn new(first: NaiveDate, last: NaiveDate) -> DateSpan {
+        DateSpan { first, last }
+    }
+
+    pub fn day(date: &NaiveDate) -> DateSpan {
+        DateSpan::new(*date, *date)
+    }
+
+    pub fn month(date: &NaiveDate) -> DateSpan {
+        let first = date.first_of_month();
+        DateSpan::new(first, first.last_of_month())
+    }
+
+    pub fn quarter(date: &NaiveDate) -> DateSpan {
+        let first = date.first_of_quarter();
+        DateSpan::new(first, first.last_of_quarter())
+    }
+
+    pub fn year(date: &NaiveDate) -> DateSpan {
+        let first = date.first_of_year();
+        DateSpan::new(first, first.last_of_year())
+    }
+
+    // The Monday to Sunday week containing `date`.
+    pub fn iso_week(date: &NaiveDate) -> DateSpan {
+        DateSpan::new(
+            date.start_of_week(Weekday::Mon),
+            date.end_of_week(Weekday::Mon),
+        )
+    }
+
+    // Parses a date expression, with relative dates counted from `today`.
+    pub fn parse_relative_to(expression: &str, today: &NaiveDate) -> Result<DateSpan> {
+        let bad_date = || WeeklyError::BadDate(expression.to_string());
+        let expression = expression.trim().to_lowercase();
+        let span = if let Some((from, to)) = expression.split_once("..") {
+            let first = parse_single(from, today).ok_or_else(bad_date)?.first;
+            let last = parse_single(to, today).ok_or_else(bad_date)?.last;
+            if last < first {
+                return Err(WeeklyError::BadDate(format!(
+                    "{} ends before it starts",
+                    expression
+                )));
+            }
+            DateSpan::new(first, last)
+        } else {
+            parse_single(&expression, today).ok_or_else(bad_date)?
+        };
+
+        // Keep to four-digit years, so that the sheets can find the months and weeks around
+        // any date without running off the end of chrono's calendar.
+        if !(YEARS.contains(&span.first.year()) && YEARS.contains(&span.last.year())) {
+            return Err(WeeklyError::BadDate(format!(
+                "{} isn't between the years {} and {}",
+                expression,
+                YEARS.start(),
+                YEARS.end()
+            )));
+        }
+        Ok(span)
+    }
+
+    pub fn first(&self) -> NaiveDate {
+        self.first
+    }
+
+    pub fn last(&self) -> NaiveDate {
+        self.last
+    }
+
+    pub fn num_days(&self) -> i64 {
+        (self.last - self.first).num_days() + 1
+    }
+
+    pub fn dates(&self) -> Vec<NaiveDate> {
+        self.first.date_range(self.num_days())
+    }
+
+    // The first day of every month that the span touches.
+    pub fn months(&self) -> Vec<NaiveDate> {
+        self.first.months_through(&self.last)
+    }
+
+    // True if the span starts on the first of a month and ends on the last day of a month.
+    pub fn is_whole_months(&self) -> bool {
+        self.first.day() == 1 && self.last.succ().day() == 1
+    }
+}
+
+// A single date, or a range, in a form that parses back into the same span.
+impl fmt::Display for DateSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.first == self.last {
+            write!(f, "{}", self.first)
+        } else {
+            write!(f, "{}..{}", self.first, self.last)
+        }
+    }
+}
+
+impl FromStr for DateSpan {
+    type Err = WeeklyError;
+
+    fn from_str(s: &str) -> Result<DateSpan> {
+        DateSpan::parse_relative_to(s, &today())
+    }
+}
+
+// The same day of the month, `months` months later, or the end of the month if it is shorter.
+// None if that is out of chrono's range.
+fn shift_months(date: &NaiveDate, months: i32) -> Option<NaiveDate> {
+    let month0 = date
+        .year()
+        .checked_mul(12)?
+        .checked_add(date.month0() as i32)?
+        .checked_add(months)?;
+    let first =
+        NaiveDate::from_ymd_opt(month0.div_euclid(12), month0.rem_euclid(12) as u32 + 1, 1)?;
+    // The latest day that the month has, for months shorter than the date's.
+    (1..=date.day()).rev().find_map(|day| first.with_day(day))
+}
+
+// Everything but ranges.
+fn parse_single(expression: &str, today: &NaiveDate) -> Option<DateSpan> {
+    match expression {
+        "today" => return Some(DateSpan::day(today)),
+        "yesterday" => return Some(DateSpan::day(&today.pred())),
+        "tomorrow" => return Some(DateSpan::day(&today.succ())),
+        _ => {}
+    }
+
+    if let Some((when, period)) = expression.split_once('-') {
+        let offset = match when {
+            "last" => Some(-1),
+            "this" => Some(0),
+            "next" => Some(1),
+            _ => None,
+        };
+        if let Some(offset) = offset {
+            return match period {
+                "week" => Some(DateSpan::iso_week(
+                    &(*today + Duration::weeks(offset as i64)),
+                )),
+                "month" => shift_months(today, offset).map(|date| DateSpan::month(&date)),
+                "quarter" => shift_months(today, offset * 3).map(|date| DateSpan::quarter(&date)),
+                "year" => shift_months(today, offset * 12).map(|date| DateSpan::year(&date)),
+                _ => None,
+            };
+        }
+    }
+
+    if expression.starts_with('+') || expression.starts_with('-') {
+        return parse_offset(expression, today).map(|date| DateSpan::day(&date));
+    }
+
+    let parts: Vec<&str> = expression.split('-').collect();
+    let year: i32 = parts[0].parse().ok().filter(|_| parts[0].len() == 4)?;
+    match parts[1..] {
+        [] => NaiveDate::from_ymd_opt(year, 1, 1).map(|date| DateSpan::year(&date)),
+        [part] if part.starts_with('q') => {
+            let quarter: u32 = part[1..].parse().ok().filter(|q| (1..=4).contains(q))?;
+            NaiveDate::from_ymd_opt(year, quarter * 3 - 2, 1).map(|date| DateSpan::quarter(&date))
+        }
+        [part] if part.starts_with('w') => {
+            let week = part[1..].parse().ok()?;
+            NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)
+                .map(|date| DateSpan::iso_week(&date))
+        }
+        [month] => {
+            NaiveDate::from_ymd_opt(year, month.parse().ok()?, 1).map(|date| DateSpan::month(&date))
+        }
+        [_, _] => expression.parse().ok().map(|date| DateSpan::day(&date)),
+        _ => None,
+    }
+}
+
+// "+3d", "-2w", "+1m" or "+1y".
+fn parse_offset(expression: &str, today: &NaiveDate) -> Option<NaiveDate> {
+    let unit = expression.chars().last()?;
+    let count: i32 = expression[..expression.len() - unit.len_utf8()]
+        .parse()
+        .ok()?;
+    match unit {
+        'd' => today.checked_add_signed(Duration::days(count as i64)),
+        'w' => today.checked_add_signed(Duration::weeks(count as i64)),
+        'm' => shift_months(today, count),
+        'y' => shift_months(today, count.checked_mul(12)?),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd(year, month, day)
+    }
+
+    fn span(expression: &str) -> Option<DateSpan> {
+        parse_single(expression, &date(2026, 12, 31))
+    }
+
+    #[test]
+    fn test_iso_weeks() {
+        assert_eq!(
+            span("2026-w53"),
+            Some(DateSpan::new(date(2026, 12, 28), date(2027, 1, 3)))
+        );
+        assert_eq!(
+            span("2026-w01"),
+            Some(DateSpan::new(date(2025, 12, 29), date(2026, 1, 4)))
+        );
+        // 2027 only has 52 weeks.
+        assert_eq!(span("2027-w53"), None);
+        assert_eq!(span("2026-w00"), None);
+    }
+
+    #[test]
+    fn test_quarters() {
+        assert_eq!(
+            span("2026-q4"),
+            Some(DateSpan::new(date(2026, 10, 1), date(2026, 12, 31)))
+        );
+        assert_eq!(span("2026-q0"), None);
+        assert_eq!(span("2026-q5"), None);
+    }
+
+    #[test]
+    fn test_months_and_years() {
+        assert_eq!(
+            span("2026-02"),
+            Some(DateSpan::new(date(2026, 2, 1), date(2026, 2, 28)))
+        );
+        assert_eq!(
+            span("2026"),
+            Some(DateSpan::new(date(2026, 1, 1), date(2026, 12, 31)))
+        );
+        assert_eq!(span("2026-13"), None);
+        assert_eq!(span("26-01"), None);
+        assert_eq!(span("2026-02-30"), None);
+    }
+
+    #[test]
+    fn test_relative_periods_roll_over_the_year() {
+        assert_eq!(
+            span("next-month"),
+            Some(DateSpan::new(date(2027, 1, 1), date(2027, 1, 31)))
+        );
+        assert_eq!(
+            span("next-quarter"),
+            Some(DateSpan::new(date(2027, 1, 1), date(2027, 3, 31)))
+        );
+        assert_eq!(
+            parse_single("last-month", &date(2027, 1, 15)),
+            Some(DateSpan::new(date(2026, 12, 1), date(2026, 12, 31)))
+        );
+        assert_eq!(span("tomorrow"), Some(DateSpan::day(&date(2027, 1, 1))));
+    }
+
+    #[test]
+    fn test_offsets() {
+        let today = date(2026, 12, 31);
+        assert_eq!(parse_offset("+1d", &today), Some(date(2027, 1, 1)));
+        assert_eq!(parse_offset("-2w", &today), Some(date(2026, 12, 17)));
+        // Shorter months end early.
+        assert_eq!(parse_offset("+2m", &today), Some(date(2027, 2, 28)));
+        assert_eq!(parse_offset("-10m", &today), Some(date(2026, 2, 28)));
+        assert_eq!(parse_offset("+1y", &today), Some(date(2027, 12, 31)));
+        assert_eq!(parse_offset("+1x", &today), None);
+        assert_eq!(parse_offset("+d", &today), None);
+    }
+
+    #[test]
+    fn test_offsets_out_of_range() {
+        let today = date(2026, 12, 31);
+        assert_eq!(parse_offset("+99999999d", &today), None);
+        assert_eq!(parse_offset("-99999999w", &today), None);
+        assert_eq!(parse_offset("+300000y", &today), None);
+        assert_eq!(parse_offset("+2147483647m", &today), None);
+        assert_eq!(parse_offset("+2147483647y", &today), None);
+    }
+
+    #[test]
+    fn test_ranges() {
+        let today = date(2026, 12, 31);
+        assert_eq!(
+            DateSpan::parse_relative_to("2026-01..2026-q1", &today).ok(),
+            Some(DateSpan::new(date(2026, 1, 1), date(2026, 3, 31)))
+        );
+        assert!(DateSpan::parse_relative_to("2026-03..2026-01", &today).is_err());
+        assert!(DateSpan::parse_relative_to("2026-03..", &today).is_err());
+        assert!(DateSpan::parse_relative_to("+9000y", &today).is_err());
+        assert!(DateSpan::parse_relative_to("0001-01..+9000y", &today).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::{Datelike, Duration, Local, NaiveDate};
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
 
 pub fn today() -> NaiveDate {
     Local::now().date().naive_local()
@@ -9,8 +9,34 @@ pub trait Datetools {
     fn dates_in_month(&self) -> Vec<NaiveDate>;
     fn num_days_in_month(&self) -> i64;
     fn first_of_month(&self) -> NaiveDate;
+    fn last_of_month(&self) -> NaiveDate;
+    fn first_of_quarter(&self) -> NaiveDate;
+    fn last_of_quarter(&self) -> NaiveDate;
+    // 1 to 4.
+    fn quarter(&self) -> u32;
+    fn next_quarter(&self) -> NaiveDate;
+    fn first_of_year(&self) -> NaiveDate;
+    fn last_of_year(&self) -> NaiveDate;
+    // The first of the month. next_month and prev_month both return the first.
     fn next_month(&self) -> NaiveDate;
+    fn prev_month(&self) -> NaiveDate;
+    fn is_weekend(&self) -> bool;
     fn date_range(&self, num_days: i64) -> Vec<NaiveDate>;
+
+    // Weeks start on `first_weekday`, so that calendars can start them on Sunday or Monday.
+    fn start_of_week(&self, first_weekday: Weekday) -> NaiveDate;
+    fn end_of_week(&self, first_weekday: Weekday) -> NaiveDate;
+    fn dates_in_week(&self, first_weekday: Weekday) -> Vec<NaiveDate>;
+    // The ISO 8601 week, 1 to 53, whose weeks start on Monday. Days near New Year's can be in a
+    // week of the year before or after.
+    fn iso_week_number(&self) -> u32;
+
+    // The first of every month from this date's month through the month of `last`.
+    fn months_through(&self, last: &NaiveDate) -> Vec<NaiveDate>;
+    // The start of every week from this date's week through the week of `last`.
+    fn weeks_through(&self, last: &NaiveDate, first_weekday: Weekday) -> Vec<NaiveDate>;
+    // The start of every week with a day in this month, which are the rows of a month calendar.
+    fn weeks_in_month(&self, first_weekday: Weekday) -> Vec<NaiveDate>;
 }
 
 impl<D> Datetools for D
@@ -43,6 +69,41 @@ where
         NaiveDate::from_ymd(self.year(), self.month(), 1)
     }
 
+    fn last_of_month(&self) -> NaiveDate {
+        self.next_month().pred()
+    }
+
+    fn first_of_quarter(&self) -> NaiveDate {
+        NaiveDate::from_ymd(self.year(), (self.quarter() - 1) * 3 + 1, 1)
+    }
+
+    fn last_of_quarter(&self) -> NaiveDate {
+        self.next_quarter().pred()
+    }
+
+    fn quarter(&self) -> u32 {
+        self.month0() / 3 + 1
+    }
+
+    fn next_quarter(&self) -> NaiveDate {
+        self.first_of_quarter()
+            .next_month()
+            .next_month()
+            .next_month()
+    }
+
+    fn first_of_year(&self) -> NaiveDate {
+        NaiveDate::from_ymd(self.year(), 1, 1)
+    }
+
+    fn last_of_year(&self) -> NaiveDate {
+        NaiveDate::from_ymd(self.year(), 12, 31)
+    }
+
+    fn is_weekend(&self) -> bool {
+        matches!(self.weekday(), Weekday::Sat | Weekday::Sun)
+    }
+
     fn next_month(&self) -> NaiveDate {
         if self.month() == 12 {
             NaiveDate::from_ymd(self.year() + 1, 1, 1)
@@ -50,4 +111,55 @@ where
             NaiveDate::from_ymd(self.year(), self.month() + 1, 1)
         }
     }
+
+    fn prev_month(&self) -> NaiveDate {
+        if self.month() == 1 {
+            NaiveDate::from_ymd(self.year() - 1, 12, 1)
+        } else {
+            NaiveDate::from_ymd(self.year(), self.month() - 1, 1)
+        }
+    }
+
+    fn start_of_week(&self, first_weekday: Weekday) -> NaiveDate {
+        let days_into_week =
+            (7 + self.weekday().num_days_from_monday() - first_weekday.num_days_from_monday()) % 7;
+        self.as_naive_date() - Duration::days(days_into_week as i64)
+    }
+
+    fn end_of_week(&self, first_weekday: Weekday) -> NaiveDate {
+        self.start_of_week(first_weekday) + Duration::days(6)
+    }
+
+    fn dates_in_week(&self, first_weekday: Weekday) -> Vec<NaiveDate> {
+        self.start_of_week(first_weekday).date_range(7)
+    }
+
+    fn iso_week_number(&self) -> u32 {
+        self.as_naive_date().iso_week().week()
+    }
+
+    fn months_through(&self, last: &NaiveDate) -> Vec<NaiveDate> {
+        let mut months = vec![];
+        let mut month = self.first_of_month();
+        while month <= *last {
+            months.push(month);
+            month = month.next_month();
+        }
+        months
+    }
+
+    fn weeks_through(&self, last: &NaiveDate, first_weekday: Weekday) -> Vec<NaiveDate> {
+        let mut weeks = vec![];
+        let mut week = self.start_of_week(first_weekday);
+        while week <= *last {
+            weeks.push(week);
+            week += Duration::weeks(1);
+        }
+        weeks
+    }
+
+    fn weeks_in_month(&self, first_weekday: Weekday) -> Vec<NaiveDate> {
+        self.first_of_month()
+            .weeks_through(&self.last_of_month(), first_weekday)
+    }
 }
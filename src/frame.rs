@@ -0,0 +1,76 @@
+use crate::{Circle, Unit, WLine, WRect};
+
+/// A region of the page described with the origin at its top-left corner and y growing down
+/// the page, which is how paper layouts are usually measured.
+///
+/// PDF coordinates put the origin at the bottom-left of the page with y growing upward. A Frame
+/// does the conversion, so every shape created through it is already in PDF coordinates and can
+/// be pushed straight onto an `Instructions`.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    bounds: WRect,
+}
+
+impl Frame {
+    pub fn new(bounds: &WRect) -> Frame {
+        Frame {
+            bounds: bounds.clone(),
+        }
+    }
+
+    pub fn width(&self) -> Unit {
+        self.bounds.width()
+    }
+
+    pub fn height(&self) -> Unit {
+        self.bounds.height()
+    }
+
+    // The area covered by the frame, in PDF coordinates.
+    pub fn bounds(&self) -> &WRect {
+        &self.bounds
+    }
+
+    // Converts a distance from the left of the frame to a PDF x coordinate.
+    pub fn x(&self, x: Unit) -> Unit {
+        self.bounds.left() + x
+    }
+
+    // Converts a distance down from the top of the frame to a PDF y coordinate.
+    pub fn y(&self, y: Unit) -> Unit {
+        self.bounds.top() - y
+    }
+
+    pub fn point(&self, x: Unit, y: Unit) -> (Unit, Unit) {
+        (self.x(x), self.y(y))
+    }
+
+    pub fn rect(&self, left: Unit, top: Unit, width: Unit, height: Unit) -> WRect {
+        WRect::with_dimensions(width, height).move_to(self.x(left), self.y(top))
+    }
+
+    pub fn line(&self, x1: Unit, y1: Unit, x2: Unit, y2: Unit) -> WLine {
+        WLine::line(self.x(x1), self.y(y1), self.x(x2), self.y(y2))
+    }
+
+    pub fn circle(&self, x: Unit, y: Unit, radius: Unit) -> Circle {
+        Circle::at_zero(radius).move_to(self.x(x), self.y(y))
+    }
+
+    // A smaller frame inside this one, with its own origin.
+    pub fn frame(&self, left: Unit, top: Unit, width: Unit, height: Unit) -> Frame {
+        Frame::new(&self.rect(left, top, width, height))
+    }
+
+    pub fn inset(&self, left: Unit, top: Unit, right: Unit, bottom: Unit) -> Frame {
+        Frame::new(&self.bounds.inset_all_q1(left, top, right, bottom))
+    }
+
+    // The position of a rect in PDF coordinates, as (left, top) measured in this frame.
+    pub fn position_of(&self, rect: &WRect) -> (Unit, Unit) {
+        (
+            rect.left() - self.bounds.left(),
+            self.bounds.top() - rect.top(),
+        )
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{Unit, WRect};
+use std::ops::Index;
+
+/// The size of one row or column in a `Layout`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Size {
+    // An exact size.
+    Fixed(Unit),
+    // A percentage of the space left once the gaps are taken out.
+    Percent(f64),
+    // A share of whatever space is left over after the Fixed and Percent tracks are placed.
+    // Space is shared in proportion to the weights, so Flex(1.0) and Flex(GOLDEN_RATIO)
+    // split the remainder in the golden ratio.
+    Flex(f64),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Direction {
+    Rows,
+    Columns,
+}
+
+/// Splits a WRect into a stack of rows (top to bottom) or columns (left to right).
+#[derive(Debug, Clone)]
+pub struct Layout {
+    direction: Direction,
+    gap: Unit,
+    tracks: Vec<(String, Size)>,
+}
+
+impl Layout {
+    pub fn rows() -> Layout {
+        Layout::new(Direction::Rows)
+    }
+
+    pub fn columns() -> Layout {
+        Layout::new(Direction::Columns)
+    }
+
+    fn new(direction: Direction) -> Layout {
+        Layout {
+            direction,
+            gap: Unit::zero(),
+            tracks: vec![],
+        }
+    }
+
+    // Space between adjacent tracks. There is no gap before the first or after the last track.
+    pub fn with_gap(mut self, gap: Unit) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    pub fn track(mut self, name: impl Into<String>, size: Size) -> Self {
+        self.tracks.push((name.into(), size));
+        self
+    }
+
+    pub fn fixed(self, name: impl Into<String>, size: Unit) -> Self {
+        self.track(name, Size::Fixed(size))
+    }
+
+    pub fn percent(self, name: impl Into<String>, percentage: f64) -> Self {
+        self.track(name, Size::Percent(percentage))
+    }
+
+    pub fn flex(self, name: impl Into<String>, weight: f64) -> Self {
+        self.track(name, Size::Flex(weight))
+    }
+
+    // Adds `count` equally sized tracks named "<prefix>0", "<prefix>1", ...
+    pub fn repeat(self, prefix: &str, count: usize) -> Self {
+        (0..count).fold(self, |layout, i| {
+            layout.flex(format!("{}{}", prefix, i), 1.0)
+        })
+    }
+
+    pub fn split(&self, rect: &WRect) -> Split {
+        let total = match self.direction {
+            Direction::Rows => rect.height(),
+            Direction::Columns => rect.width(),
+        };
+        let num_gaps = self.tracks.len().saturating_sub(1);
+        let available = total - self.gap * num_gaps as f64;
+
+        let sized: Unit = self
+            .tracks
+            .iter()
+            .map(|(_, size)| match size {
+                Size::Fixed(unit) => *unit,
+                Size::Percent(pct) => available.pct(*pct),
+                Size::Flex(_) => Unit::zero(),
+            })
+            .fold(Unit::zero(), |a, b| a + b);
+        let total_weight: f64 = self
+            .tracks
+            .iter()
+            .map(|(_, size)| match size {
+                Size::Flex(weight) => *weight,
+                _ => 0.0,
+            })
+            .sum();
+        let remainder = (available - sized).max(Unit::zero());
+
+        let mut offset = Unit::zero();
+        let mut rects = Vec::with_capacity(self.tracks.len());
+        for (name, size) in &self.tracks {
+            let length = match size {
+                Size::Fixed(unit) => *unit,
+                Size::Percent(pct) => available.pct(*pct),
+                Size::Flex(weight) if total_weight > 0.0 => remainder * (weight / total_weight),
+                Size::Flex(_) => Unit::zero(),
+            };
+
+            // In Q1, rows move down the page as the top gets smaller.
+            let track_rect = match self.direction {
+                Direction::Rows => rect
+                    .resize(rect.width(), length)
+                    .move_to(rect.left(), rect.top() - offset),
+                Direction::Columns => rect
+                    .resize(length, rect.height())
+                    .move_to(rect.left() + offset, rect.top()),
+            };
+            rects.push((name.clone(), track_rect));
+
+            offset = offset + length + self.gap;
+        }
+
+        Split { rects }
+    }
+}
+
+/// The rects produced by `Layout::split()`, in order, and addressable by name.
+#[derive(Debug, Clone)]
+pub struct Split {
+    rects: Vec<(String, WRect)>,
+}
+
+impl Split {
+    pub fn get(&self, name: &str) -> Option<&WRect> {
+        self.rects
+            .iter()
+            .find(|(track_name, _)| track_name == name)
+            .map(|(_, rect)| rect)
+    }
+
+    pub fn len(&self) -> usize {
+        self.rects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &WRect> {
+        self.rects.iter().map(|(_, rect)| rect)
+    }
+}
+
+impl Index<&str> for Split {
+    type Output = WRect;
+
+    fn index(&self, name: &str) -> &Self::Output {
+        self.get(name)
+            .unwrap_or_else(|| panic!("no track named '{}' in layout", name))
+    }
+}
+
+impl Index<usize> for Split {
+    type Output = WRect;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.rects[index].1
+    }
+}
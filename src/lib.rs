@@ -1,23 +1,39 @@
 use thiserror::Error;
 
+pub use calendar::MonthCalendar;
+pub use datespan::DateSpan;
 pub use datetools::{today, Datetools};
+pub use frame::Frame;
+pub use layout::{Layout, Size, Split};
 pub use pdfutils::{
-    save_double_sided_document, save_one_page_document, sizes, Attributes, Colors, FontProxy,
-    Instructions, TextContext,
+    save_document, save_double_sided_document, save_multi_page_document, save_one_page_document,
+    sizes, write_document, write_double_sided_document, write_multi_page_document,
+    write_one_page_document, Attributes, Colors, Component, DocumentMetadata, FieldKind, FontProxy,
+    FormField, Instructions, LinkTarget, Margins, PageSetup, TextContext, Transform,
 };
 pub use shapes::circle::Circle;
 pub use shapes::line::WLine;
 pub use shapes::rect::WRect;
 pub use shapes::{HasRenderAttrs, ToPdfLine};
-pub use tgrid::description::GridDescription;
+pub use tabs::Tabs;
+pub use tgrid::cells;
+pub use tgrid::description::{GridDescription, LabelOrientation};
 pub use tgrid::TGrid;
 pub use units::{NumericUnit, Unit};
+pub use watch::watch_files;
 
+mod calendar;
+mod datespan;
 mod datetools;
+mod frame;
+mod layout;
+pub mod patterns;
 mod pdfutils;
 mod shapes;
+mod tabs;
 mod tgrid;
 mod units;
+mod watch;
 
 #[derive(Debug, Error)]
 pub enum WeeklyError {
@@ -26,6 +42,18 @@ pub enum WeeklyError {
 
     #[error("PrintPdf error: {0}")]
     PrintPdfError(#[from] printpdf::Error),
+
+    #[error("PDF error: {0}")]
+    LopdfError(#[from] printpdf::lopdf::Error),
+
+    #[error("Link to something that isn't in the document: {0}")]
+    UnknownLinkTarget(String),
+
+    #[error("{0}")]
+    ConfigError(String),
+
+    #[error("Bad date: {0}")]
+    BadDate(String),
 }
 
 pub type Result<T> = std::result::Result<T, WeeklyError>;
@@ -0,0 +1,767 @@
+//! Background patterns for filling a rect: dot grids, square grids, graph paper, isometric and
+//! hex grids, music staves and ruled paper.
+//!
+//! Each pattern is drawn with its `Attributes`, so the weight and color of its lines are
+//! configurable. Dots are filled, so they take the fill color.
+
+use crate::{
+    sizes, Attributes, Circle, Colors, Component, HasRenderAttrs, Instructions, NumericUnit,
+    TextContext, Unit, WLine, WRect,
+};
+
+pub trait Pattern {
+    fn render(&self, rect: &WRect, instructions: &mut Instructions);
+}
+
+/// Where a pattern sits in the rect that it fills.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum PatternAlignment {
+    // The pattern starts in the top-left corner of the rect. Any space left over is at the right
+    // and bottom.
+    #[default]
+    TopLeft,
+    // The space left over is shared equally on all sides.
+    Center,
+}
+
+impl PatternAlignment {
+    // Offsets of as many evenly spaced marks, `pitch` apart, as will fit in `length`.
+    fn offsets(&self, length: Unit, pitch: Unit) -> Vec<Unit> {
+        if pitch <= Unit::zero() || length < Unit::zero() {
+            return vec![];
+        }
+
+        // The fudge keeps rounding errors from dropping the last mark when it is right at the end.
+        let count = (length / pitch + 1e-6).floor() as usize + 1;
+        let first = match self {
+            PatternAlignment::TopLeft => Unit::zero(),
+            PatternAlignment::Center => (length - pitch * (count - 1) as f64) / 2,
+        };
+        (0..count).map(|i| first + pitch * i as f64).collect()
+    }
+
+    // The x and y coordinates of a square lattice in `rect`.
+    fn lattice(&self, rect: &WRect, pitch: Unit) -> (Vec<Unit>, Vec<Unit>) {
+        let xs = self
+            .offsets(rect.width(), pitch)
+            .into_iter()
+            .map(|x| rect.left() + x)
+            .collect();
+        let ys = self
+            .offsets(rect.height(), pitch)
+            .into_iter()
+            .map(|y| rect.top() - y)
+            .collect();
+        (xs, ys)
+    }
+}
+
+fn horizontal_lines(ys: &[Unit], left: Unit, right: Unit, instructions: &mut Instructions) {
+    for y in ys {
+        instructions.push_shape(WLine::line(left, *y, right, *y).stroke());
+    }
+}
+
+fn vertical_lines(xs: &[Unit], top: Unit, bottom: Unit, instructions: &mut Instructions) {
+    for x in xs {
+        instructions.push_shape(WLine::line(*x, top, *x, bottom).stroke());
+    }
+}
+
+// A column of dots at x = 0. Columns of dots are placed many times, so they are Components.
+fn dot_column(ys: &[Unit], radius: Unit) -> Component {
+    let base_circle = Circle::at_zero(radius);
+    let mut column = Instructions::default();
+    for y in ys {
+        column.push_shape(base_circle.move_to(Unit::zero(), *y).fill());
+    }
+
+    let (top, bottom) = ends(ys).unwrap_or_default();
+    let bounds = WRect::with_dimensions(radius * 2, top - bottom + radius * 2)
+        .move_to(-radius, top + radius);
+    Component::new(&bounds, column)
+}
+
+// The first and last of a non-empty list of coordinates.
+fn ends(coords: &[Unit]) -> Option<(Unit, Unit)> {
+    Some((*coords.first()?, *coords.last()?))
+}
+
+/// Dots on a square grid.
+#[derive(Debug, Clone)]
+pub struct DotGrid {
+    pitch: Unit,
+    radius: Unit,
+    alignment: PatternAlignment,
+    attributes: Attributes,
+}
+
+impl DotGrid {
+    pub fn new(pitch: Unit) -> DotGrid {
+        DotGrid {
+            pitch,
+            radius: 0.25.mm(),
+            alignment: PatternAlignment::default(),
+            attributes: Attributes::default(),
+        }
+    }
+
+    pub fn with_radius(mut self, radius: Unit) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn with_alignment(mut self, alignment: PatternAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+}
+
+impl Pattern for DotGrid {
+    fn render(&self, rect: &WRect, instructions: &mut Instructions) {
+        let (xs, ys) = self.alignment.lattice(rect, self.pitch);
+
+        // Every column of dots is the same, so draw one and place it across the rect.
+        let column = dot_column(&ys, self.radius);
+        self.attributes.render(instructions, |instructions| {
+            for x in xs {
+                instructions.place_at(&column, x, Unit::zero());
+            }
+        });
+    }
+}
+
+/// Evenly spaced horizontal and vertical lines.
+#[derive(Debug, Clone)]
+pub struct SquareGrid {
+    pitch: Unit,
+    alignment: PatternAlignment,
+    attributes: Attributes,
+}
+
+impl SquareGrid {
+    pub fn new(pitch: Unit) -> SquareGrid {
+        SquareGrid {
+            pitch,
+            alignment: PatternAlignment::default(),
+            attributes: Attributes::default(),
+        }
+    }
+
+    pub fn with_alignment(mut self, alignment: PatternAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+}
+
+impl Pattern for SquareGrid {
+    fn render(&self, rect: &WRect, instructions: &mut Instructions) {
+        let (xs, ys) = self.alignment.lattice(rect, self.pitch);
+        if let (Some((left, right)), Some((top, bottom))) = (ends(&xs), ends(&ys)) {
+            self.attributes.render(instructions, |instructions| {
+                horizontal_lines(&ys, left, right, instructions);
+                vertical_lines(&xs, top, bottom, instructions);
+            });
+        }
+    }
+}
+
+/// A square grid with every `major_every`th line drawn in a heavier style.
+#[derive(Debug, Clone)]
+pub struct GraphPaper {
+    minor_pitch: Unit,
+    major_every: usize,
+    alignment: PatternAlignment,
+    minor_attributes: Attributes,
+    major_attributes: Attributes,
+}
+
+impl GraphPaper {
+    pub fn new(minor_pitch: Unit, major_every: usize) -> GraphPaper {
+        GraphPaper {
+            minor_pitch,
+            major_every,
+            alignment: PatternAlignment::default(),
+            minor_attributes: Attributes::default().with_stroke_width(0.25),
+            major_attributes: Attributes::default().with_stroke_width(0.75),
+        }
+    }
+
+    pub fn with_alignment(mut self, alignment: PatternAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn with_minor_attributes(mut self, attributes: Attributes) -> Self {
+        self.minor_attributes = attributes;
+        self
+    }
+
+    pub fn with_major_attributes(mut self, attributes: Attributes) -> Self {
+        self.major_attributes = attributes;
+        self
+    }
+
+    // Splits the coordinates into the minor and the major lines.
+    fn partition(&self, coords: &[Unit]) -> (Vec<Unit>, Vec<Unit>) {
+        let major_every = self.major_every.max(1);
+        let (major, minor): (Vec<_>, Vec<_>) = coords
+            .iter()
+            .enumerate()
+            .partition(|(index, _)| index.is_multiple_of(major_every));
+        (
+            minor.into_iter().map(|(_, coord)| *coord).collect(),
+            major.into_iter().map(|(_, coord)| *coord).collect(),
+        )
+    }
+}
+
+impl Pattern for GraphPaper {
+    fn render(&self, rect: &WRect, instructions: &mut Instructions) {
+        let (xs, ys) = self.alignment.lattice(rect, self.minor_pitch);
+        if let (Some((left, right)), Some((top, bottom))) = (ends(&xs), ends(&ys)) {
+            let (minor_xs, major_xs) = self.partition(&xs);
+            let (minor_ys, major_ys) = self.partition(&ys);
+
+            // The major lines go on top, so that they aren't broken by the minor lines.
+            self.minor_attributes.render(instructions, |instructions| {
+                horizontal_lines(&minor_ys, left, right, instructions);
+                vertical_lines(&minor_xs, top, bottom, instructions);
+            });
+            self.major_attributes.render(instructions, |instructions| {
+                horizontal_lines(&major_ys, left, right, instructions);
+                vertical_lines(&major_xs, top, bottom, instructions);
+            });
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum IsometricStyle {
+    Dots(Unit),
+    Lines,
+}
+
+/// A triangular grid for isometric drawing, with vertical lines and lines at 30° either side of
+/// horizontal, or just the dots where they meet.
+#[derive(Debug, Clone)]
+pub struct IsometricGrid {
+    pitch: Unit,
+    style: IsometricStyle,
+    alignment: PatternAlignment,
+    attributes: Attributes,
+}
+
+impl IsometricGrid {
+    // `pitch` is the distance between neighboring dots.
+    pub fn dots(pitch: Unit) -> IsometricGrid {
+        IsometricGrid::new(pitch, IsometricStyle::Dots(0.25.mm()))
+    }
+
+    pub fn lines(pitch: Unit) -> IsometricGrid {
+        IsometricGrid::new(pitch, IsometricStyle::Lines)
+    }
+
+    fn new(pitch: Unit, style: IsometricStyle) -> IsometricGrid {
+        IsometricGrid {
+            pitch,
+            style,
+            alignment: PatternAlignment::default(),
+            attributes: Attributes::default(),
+        }
+    }
+
+    // Only used for dots.
+    pub fn with_radius(mut self, radius: Unit) -> Self {
+        if let IsometricStyle::Dots(_) = self.style {
+            self.style = IsometricStyle::Dots(radius);
+        }
+        self
+    }
+
+    pub fn with_alignment(mut self, alignment: PatternAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    fn render_dots(&self, radius: Unit, xs: &[Unit], ys: &[Unit], instructions: &mut Instructions) {
+        // Odd columns are shifted down half a pitch, so they may have one less dot.
+        let bottom = ys.last().copied().unwrap_or_default();
+        let odd_ys: Vec<Unit> = ys
+            .iter()
+            .map(|y| *y - self.pitch / 2)
+            .filter(|y| *y >= bottom)
+            .collect();
+
+        let even_column = dot_column(ys, radius);
+        let odd_column = dot_column(&odd_ys, radius);
+        for (column, x) in xs.iter().enumerate() {
+            if column % 2 == 1 {
+                instructions.place_at(&odd_column, *x, Unit::zero());
+            } else {
+                instructions.place_at(&even_column, *x, Unit::zero());
+            }
+        }
+    }
+
+    fn render_lines(&self, xs: &[Unit], ys: &[Unit], instructions: &mut Instructions) {
+        let (left, right) = ends(xs).unwrap_or_default();
+        let (top, bottom) = ends(ys).unwrap_or_default();
+        let width = right - left;
+
+        vertical_lines(xs, top, bottom, instructions);
+
+        // The sloped lines are drawn through each point in the first column, and far enough above
+        // and below it to cover the whole grid. Anything outside of the grid is clipped.
+        instructions.push_state();
+        instructions.clip_to(WRect::with_dimensions(width, top - bottom).move_to(left, top));
+        let rise = width * 30.0_f64.to_radians().tan();
+        let extra = (rise / self.pitch).ceil() as i64;
+        let count = ((top - bottom) / self.pitch).round() as i64;
+        for k in -extra..=count + extra {
+            let y = top - self.pitch * k as f64;
+            instructions.push_shape(WLine::line(left, y, right, y - rise).stroke());
+            instructions.push_shape(WLine::line(left, y, right, y + rise).stroke());
+        }
+        instructions.pop_state();
+    }
+}
+
+impl Pattern for IsometricGrid {
+    fn render(&self, rect: &WRect, instructions: &mut Instructions) {
+        let column_pitch = self.pitch * (3.0_f64.sqrt() / 2.0);
+        let xs: Vec<Unit> = self
+            .alignment
+            .offsets(rect.width(), column_pitch)
+            .into_iter()
+            .map(|x| rect.left() + x)
+            .collect();
+        let ys: Vec<Unit> = self
+            .alignment
+            .offsets(rect.height(), self.pitch)
+            .into_iter()
+            .map(|y| rect.top() - y)
+            .collect();
+        if xs.is_empty() || ys.is_empty() {
+            return;
+        }
+
+        self.attributes
+            .render(instructions, |instructions| match self.style {
+                IsometricStyle::Dots(radius) => self.render_dots(radius, &xs, &ys, instructions),
+                IsometricStyle::Lines => self.render_lines(&xs, &ys, instructions),
+            });
+    }
+}
+
+/// Flat-topped hexagons, packed edge to edge.
+#[derive(Debug, Clone)]
+pub struct HexGrid {
+    side: Unit,
+    alignment: PatternAlignment,
+    attributes: Attributes,
+}
+
+impl HexGrid {
+    // `side` is the length of each side of a hexagon. The hexagons are twice that wide.
+    pub fn new(side: Unit) -> HexGrid {
+        HexGrid {
+            side,
+            alignment: PatternAlignment::default(),
+            attributes: Attributes::default(),
+        }
+    }
+
+    pub fn with_alignment(mut self, alignment: PatternAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    // A single hexagon, centered on the origin.
+    fn hexagon(&self) -> Component {
+        let mut instructions = Instructions::default();
+        let corners: Vec<(Unit, Unit)> = (0..=6)
+            .map(|corner| {
+                let angle = (60.0 * corner as f64).to_radians();
+                (self.side * angle.cos(), self.side * angle.sin())
+            })
+            .collect();
+        for pair in corners.windows(2) {
+            let ((x1, y1), (x2, y2)) = (pair[0], pair[1]);
+            instructions.push_shape(WLine::line(x1, y1, x2, y2).stroke());
+        }
+
+        // Leave room for the width of the lines.
+        let padding = 1.0.mm();
+        let size = self.side * 2 + padding * 2;
+        let bounds = WRect::with_dimensions(size, size).move_to(-size / 2, size / 2);
+        Component::new(&bounds, instructions)
+    }
+}
+
+impl Pattern for HexGrid {
+    fn render(&self, rect: &WRect, instructions: &mut Instructions) {
+        let width = self.side * 2;
+        let height = self.side * 3.0_f64.sqrt();
+
+        let column_offsets = self
+            .alignment
+            .offsets(rect.width() - width, self.side * 1.5);
+        let row_offsets = self.alignment.offsets(rect.height() - height, height);
+        let last_row = row_offsets.last().copied().unwrap_or_default();
+
+        let hexagon = self.hexagon();
+        self.attributes.render(instructions, |instructions| {
+            for (column, x_offset) in column_offsets.iter().enumerate() {
+                // Odd columns are shifted down by half a hexagon, so they may have one less.
+                let shift = if column % 2 == 1 {
+                    height / 2
+                } else {
+                    Unit::zero()
+                };
+                for y_offset in &row_offsets {
+                    let y_offset = *y_offset + shift;
+                    if y_offset <= last_row {
+                        instructions.place_at(
+                            &hexagon,
+                            rect.left() + *x_offset + width / 2,
+                            rect.top() - y_offset - height / 2,
+                        );
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Five-line music staves, stacked down the rect.
+#[derive(Debug, Clone)]
+pub struct MusicStaff {
+    line_spacing: Unit,
+    staff_gap: Unit,
+    alignment: PatternAlignment,
+    attributes: Attributes,
+}
+
+impl MusicStaff {
+    pub fn new(line_spacing: Unit) -> MusicStaff {
+        MusicStaff {
+            line_spacing,
+            staff_gap: line_spacing * 6,
+            alignment: PatternAlignment::default(),
+            attributes: Attributes::default(),
+        }
+    }
+
+    // Space between the bottom line of one staff and the top line of the next.
+    pub fn with_staff_gap(mut self, staff_gap: Unit) -> Self {
+        self.staff_gap = staff_gap;
+        self
+    }
+
+    pub fn with_alignment(mut self, alignment: PatternAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+}
+
+impl Pattern for MusicStaff {
+    fn render(&self, rect: &WRect, instructions: &mut Instructions) {
+        let staff_height = self.line_spacing * 4;
+        let staff_tops = self
+            .alignment
+            .offsets(rect.height() - staff_height, staff_height + self.staff_gap);
+
+        self.attributes.render(instructions, |instructions| {
+            for staff_top in staff_tops {
+                let ys: Vec<Unit> = (0..5)
+                    .map(|line| rect.top() - staff_top - self.line_spacing * line as f64)
+                    .collect();
+                horizontal_lines(&ys, rect.left(), rect.right(), instructions);
+            }
+        });
+    }
+}
+
+/// The standard spacings between the lines of ruled paper.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RuleHeight {
+    College,
+    Wide,
+    Narrow,
+    Gregg,
+    Custom(Unit),
+}
+
+impl RuleHeight {
+    pub fn height(&self) -> Unit {
+        match self {
+            RuleHeight::College => sizes::college_rule_height(),
+            RuleHeight::Wide => sizes::wide_rule_height(),
+            RuleHeight::Narrow => sizes::narrow_rule_height(),
+            RuleHeight::Gregg => sizes::gregg_rule_height(),
+            RuleHeight::Custom(height) => *height,
+        }
+    }
+}
+
+/// Guides showing where the holes will be punched, so that nothing important is put there.
+/// The holes are centered vertically on the paper, and measured from its left edge. Without a
+/// paper rect, the ruled rect is taken to be the whole paper.
+#[derive(Debug, Clone)]
+pub struct HolePunch {
+    count: usize,
+    spacing: Unit,
+    diameter: Unit,
+    edge_offset: Unit,
+    paper: Option<WRect>,
+}
+
+impl HolePunch {
+    // US loose-leaf paper.
+    pub fn three_hole() -> HolePunch {
+        HolePunch::new(3, 4.25.inches(), 0.25.inches(), 0.5.inches())
+    }
+
+    // ISO 838.
+    pub fn two_hole() -> HolePunch {
+        HolePunch::new(2, 80.0.mm(), 6.0.mm(), 12.0.mm())
+    }
+
+    pub fn new(count: usize, spacing: Unit, diameter: Unit, edge_offset: Unit) -> HolePunch {
+        HolePunch {
+            count,
+            spacing,
+            diameter,
+            edge_offset,
+            paper: None,
+        }
+    }
+
+    // Distance from the left edge of the paper to the center of the holes.
+    pub fn with_edge_offset(mut self, edge_offset: Unit) -> Self {
+        self.edge_offset = edge_offset;
+        self
+    }
+
+    // The edges of the paper, like `PageSetup::trim_rect()`, in the same coordinates as the rect
+    // that the ruled paper is rendered in.
+    pub fn with_paper(mut self, paper: &WRect) -> Self {
+        self.paper = Some(paper.clone());
+        self
+    }
+
+    fn render(&self, rect: &WRect, instructions: &mut Instructions) {
+        if self.count == 0 {
+            return;
+        }
+        let paper = self.paper.as_ref().unwrap_or(rect);
+        let x = paper.left() + self.edge_offset;
+        let middle = paper.bottom_q1() + paper.height() / 2;
+        let first = middle + self.spacing * (self.count - 1) as f64 / 2;
+        let hole = Circle::at_zero(self.diameter / 2);
+        for i in 0..self.count {
+            let y = first - self.spacing * i as f64;
+            instructions.push_shape(hole.move_to(x, y).stroke());
+        }
+    }
+}
+
+/// Horizontal lines for writing on, like notebook paper.
+///
+/// The lines run the full width of the rect. Optionally, there is a header band at the top, a
+/// margin line down the left side, guides for punched holes, and line numbers.
+#[derive(Debug, Clone)]
+pub struct RuledPaper {
+    rule_height: Unit,
+    first_line: Unit,
+    header_height: Option<Unit>,
+    margin_line: Option<Unit>,
+    hole_punch: Option<HolePunch>,
+    line_numbers: Option<TextContext>,
+    line_attributes: Attributes,
+    margin_attributes: Attributes,
+    header_attributes: Attributes,
+    guide_attributes: Attributes,
+}
+
+impl RuledPaper {
+    pub fn new(rule_height: RuleHeight) -> RuledPaper {
+        let rule_height = rule_height.height();
+        RuledPaper {
+            rule_height,
+            first_line: rule_height,
+            header_height: None,
+            margin_line: None,
+            hole_punch: None,
+            line_numbers: None,
+            line_attributes: Attributes::default(),
+            margin_attributes: Attributes::default()
+                .with_stroke_color(&Colors::rgb(0.9, 0.4, 0.4))
+                .with_stroke_width(0.75),
+            header_attributes: Attributes::default().with_stroke_width(1.0),
+            guide_attributes: Attributes::default()
+                .with_stroke_color(&Colors::gray(0.8))
+                .with_stroke_width(0.0),
+        }
+    }
+
+    // Distance from the top of the ruled area (below any header) to the first line.
+    // Defaults to one rule height, so there is room to write above the first line.
+    pub fn with_first_line(mut self, first_line: Unit) -> Self {
+        self.first_line = first_line;
+        self
+    }
+
+    // A band across the top with no lines, ended by a heavier line.
+    pub fn with_header(mut self, height: Unit) -> Self {
+        self.header_height = Some(height);
+        self
+    }
+
+    // A vertical line this far in from the left of the rect.
+    pub fn with_margin_line(mut self, offset: Unit) -> Self {
+        self.margin_line = Some(offset);
+        self
+    }
+
+    pub fn with_hole_punch(mut self, hole_punch: HolePunch) -> Self {
+        self.hole_punch = Some(hole_punch);
+        self
+    }
+
+    // Numbers each writing line. The numbers go just left of the margin line, if there is one,
+    // or at the left of the rect.
+    pub fn with_line_numbers(mut self, text_context: &TextContext) -> Self {
+        self.line_numbers = Some(text_context.clone());
+        self
+    }
+
+    pub fn with_line_attributes(mut self, attributes: Attributes) -> Self {
+        self.line_attributes = attributes;
+        self
+    }
+
+    pub fn with_margin_attributes(mut self, attributes: Attributes) -> Self {
+        self.margin_attributes = attributes;
+        self
+    }
+
+    pub fn with_header_attributes(mut self, attributes: Attributes) -> Self {
+        self.header_attributes = attributes;
+        self
+    }
+
+    pub fn with_guide_attributes(mut self, attributes: Attributes) -> Self {
+        self.guide_attributes = attributes;
+        self
+    }
+
+    // The part of the rect below the header.
+    fn ruled_rect(&self, rect: &WRect) -> WRect {
+        let header_height = self.header_height.unwrap_or_default().min(rect.height());
+        rect.inset_all_q1(Unit::zero(), header_height, Unit::zero(), Unit::zero())
+    }
+
+    // The y coordinates of the ruled lines.
+    pub fn line_ys(&self, rect: &WRect) -> Vec<Unit> {
+        let ruled_rect = self.ruled_rect(rect);
+        PatternAlignment::TopLeft
+            .offsets(ruled_rect.height() - self.first_line, self.rule_height)
+            .into_iter()
+            .map(|y| ruled_rect.top() - self.first_line - y)
+            .collect()
+    }
+
+    // The writing space above each ruled line that has a full rule height above it, from the top.
+    // These can be used to put things, like checkboxes, on the lines.
+    pub fn line_rects(&self, rect: &WRect) -> Vec<WRect> {
+        let ruled_top = self.ruled_rect(rect).top();
+        self.line_ys(rect)
+            .into_iter()
+            .filter(|y| *y + self.rule_height <= ruled_top + 0.01.mm())
+            .map(|y| {
+                WRect::with_dimensions(rect.width(), self.rule_height)
+                    .move_to(rect.left(), y + self.rule_height)
+            })
+            .collect()
+    }
+
+    fn render_line_numbers(
+        &self,
+        text_context: &TextContext,
+        rect: &WRect,
+        instructions: &mut Instructions,
+    ) {
+        let font = text_context.font();
+        let font_size = text_context.text_height().to_mm();
+        let padding = 1.0.mm();
+        for (index, line_rect) in self.line_rects(rect).iter().enumerate() {
+            let number = (index + 1).to_string();
+            let x = match self.margin_line {
+                Some(offset) => {
+                    rect.left() + offset - padding - font.text_width(&number, font_size)
+                }
+                None => rect.left() + padding,
+            };
+            text_context.render(&number, x, line_rect.bottom_q1() + padding, instructions);
+        }
+    }
+}
+
+impl Pattern for RuledPaper {
+    fn render(&self, rect: &WRect, instructions: &mut Instructions) {
+        if let Some(hole_punch) = &self.hole_punch {
+            self.guide_attributes.render(instructions, |instructions| {
+                hole_punch.render(rect, instructions);
+            });
+        }
+
+        self.line_attributes.render(instructions, |instructions| {
+            horizontal_lines(&self.line_ys(rect), rect.left(), rect.right(), instructions);
+        });
+
+        if self.header_height.is_some() {
+            let y = self.ruled_rect(rect).top();
+            self.header_attributes.render(instructions, |instructions| {
+                horizontal_lines(&[y], rect.left(), rect.right(), instructions);
+            });
+        }
+
+        if let Some(offset) = self.margin_line {
+            let x = rect.left() + offset;
+            self.margin_attributes.render(instructions, |instructions| {
+                vertical_lines(&[x], rect.top(), rect.bottom_q1(), instructions);
+            });
+        }
+
+        if let Some(text_context) = &self.line_numbers {
+            self.render_line_numbers(text_context, rect, instructions);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use crate::pdfutils::fnv::FnvHasher;
+use crate::{Instructions, Result, WRect};
+use printpdf::lopdf::content::Content;
+use printpdf::lopdf::{self, dictionary, Dictionary, Object, ObjectId, Stream};
+use printpdf::Pt;
+use std::hash::Hasher;
+use std::rc::Rc;
+
+/// A drawing that is defined once and placed many times with `Instructions::place()`.
+///
+/// Components are written to the PDF as Form XObjects, so each placement costs a few bytes
+/// instead of repeating every shape. Components are cheap to clone, and may place other
+/// components.
+#[derive(Debug, Clone)]
+pub struct Component(Rc<ComponentData>);
+
+#[derive(Debug)]
+struct ComponentData {
+    id: u64,
+    bounds: WRect,
+    instructions: Instructions,
+}
+
+impl Component {
+    // `bounds` is the area, in the component's own coordinates, that the instructions draw in.
+    // Anything drawn outside of it is clipped.
+    pub fn new(bounds: &WRect, instructions: Instructions) -> Component {
+        // The id comes from the contents, rather than a counter, so that a document's XObject
+        // names don't depend on what else the program has drawn. Equal components share a name,
+        // which is fine, since either can be drawn in place of the other.
+        let mut hasher = FnvHasher::default();
+        for unit in [
+            bounds.left(),
+            bounds.bottom_q1(),
+            bounds.right(),
+            bounds.top(),
+        ] {
+            hasher.write(&Pt::from(unit).0.to_le_bytes());
+        }
+        // The content stream that the component will be written as. Encoding into memory can't
+        // fail.
+        let content = Content {
+            operations: instructions.operations(),
+        };
+        hasher.write(&content.encode().unwrap_or_default());
+        Component(Rc::new(ComponentData {
+            id: hasher.finish(),
+            bounds: bounds.clone(),
+            instructions,
+        }))
+    }
+
+    pub fn bounds(&self) -> &WRect {
+        &self.0.bounds
+    }
+
+    pub(crate) fn instructions(&self) -> &Instructions {
+        &self.0.instructions
+    }
+
+    // The name of the XObject in the page resources.
+    pub(crate) fn name(&self) -> String {
+        format!("Cmp{:016x}", self.0.id)
+    }
+
+    fn to_form_xobject(&self, resources: &Dictionary) -> Result<Stream> {
+        let bounds = self.bounds();
+        let bbox: Vec<Object> = [
+            bounds.left(),
+            bounds.bottom_q1(),
+            bounds.right(),
+            bounds.top(),
+        ]
+        .iter()
+        .map(|unit| Pt::from(unit).0.into())
+        .collect();
+
+        let content = Content {
+            operations: self.instructions().operations(),
+        };
+        let mut stream = Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Form",
+                "BBox" => bbox,
+                "Resources" => resources.clone(),
+            },
+            content.encode()?,
+        );
+        stream.compress()?;
+        Ok(stream)
+    }
+}
+
+// Adds every component to `pdf` as a Form XObject, and makes them available to every page.
+pub(crate) fn embed_components(pdf: &mut lopdf::Document, components: &[Component]) -> Result<()> {
+    if components.is_empty() {
+        return Ok(());
+    }
+
+    // Fonts are shared by the whole document, so the components use the same ones as the pages.
+    let pages: Vec<ObjectId> = pdf.get_pages().values().cloned().collect();
+    let font_dict = pages
+        .iter()
+        .filter_map(|page_id| page_resources(pdf, *page_id))
+        .find_map(|resources| resources.get(b"Font").ok().cloned());
+
+    // Components may place each other, so they share one XObject dictionary with the pages.
+    let xobjects_id = pdf.new_object_id();
+    let mut resources = dictionary! { "XObject" => xobjects_id };
+    if let Some(font_dict) = font_dict {
+        resources.set("Font", font_dict);
+    }
+
+    let mut xobjects = Dictionary::new();
+    for component in components {
+        let form_id = pdf.add_object(component.to_form_xobject(&resources)?);
+        xobjects.set(component.name(), form_id);
+    }
+    pdf.objects
+        .insert(xobjects_id, Object::Dictionary(xobjects));
+
+    for page_id in pages {
+        let resources_id = match pdf.get_dictionary(page_id)?.get(b"Resources") {
+            Ok(Object::Reference(id)) => Some(*id),
+            _ => None,
+        };
+        let page_resources = match resources_id {
+            Some(id) => pdf.get_object_mut(id)?.as_dict_mut()?,
+            None => {
+                let page = pdf.get_object_mut(page_id)?.as_dict_mut()?;
+                if !page.has(b"Resources") {
+                    page.set("Resources", Dictionary::new());
+                }
+                page.get_mut(b"Resources")?.as_dict_mut()?
+            }
+        };
+        page_resources.set("XObject", xobjects_id);
+    }
+    Ok(())
+}
+
+fn page_resources(pdf: &lopdf::Document, page_id: ObjectId) -> Option<&Dictionary> {
+    match pdf.get_dictionary(page_id).ok()?.get(b"Resources").ok()? {
+        Object::Reference(id) => pdf.get_dictionary(*id).ok(),
+        Object::Dictionary(dict) => Some(dict),
+        _ => None,
+    }
+}
@@ -0,0 +1,31 @@
+use std::hash::Hasher;
+
+// 64-bit FNV-1a, from http://www.isthe.com/chongo/tech/comp/fnv/.
+//
+// The hashes are written into documents, so they have to be the same on every machine and with
+// every Rust release, which std's DefaultHasher doesn't promise. Only feed it bytes with write():
+// the Hash impls for integers depend on the platform's byte order and word size.
+const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const PRIME: u64 = 0x0100_0000_01b3;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        FnvHasher(OFFSET_BASIS)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(PRIME);
+        }
+    }
+}
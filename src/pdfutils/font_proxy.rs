@@ -1,5 +1,10 @@
+use crate::pdfutils::font_metrics;
+use crate::{NumericUnit, Unit};
 use printpdf::BuiltinFont;
 
+// Number of millimeters in a typographic point.
+const MM_PER_POINT: f64 = 25.4 / 72.0;
+
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
 pub enum FontProxy {
     // first bool is Bold, second bool is Italics
@@ -26,6 +31,31 @@ impl FontProxy {
             FontProxy::Times(_, it) => FontProxy::Times(bold, *it),
         }
     }
+
+    // Returns the width of `s` when rendered at `font_size` (in points).
+    pub fn text_width(&self, s: &str, font_size: f64) -> Unit {
+        let widths = match self {
+            FontProxy::Helvetica(false, _) => &font_metrics::HELVETICA,
+            FontProxy::Helvetica(true, _) => &font_metrics::HELVETICA_BOLD,
+            FontProxy::Times(false, _) => &font_metrics::TIMES,
+            FontProxy::Times(true, _) => &font_metrics::TIMES_BOLD,
+        };
+        Self::em_fraction(font_metrics::string_width(widths, s), font_size)
+    }
+
+    // Returns the height of a capital letter above the baseline at `font_size` (in points).
+    pub fn cap_height(&self, font_size: f64) -> Unit {
+        let cap_height = match self {
+            FontProxy::Helvetica(_, _) => font_metrics::HELVETICA_CAP_HEIGHT,
+            FontProxy::Times(_, _) => font_metrics::TIMES_CAP_HEIGHT,
+        };
+        Self::em_fraction(cap_height as u32, font_size)
+    }
+
+    // Converts thousandths of an em to a Unit.
+    fn em_fraction(thousandths: u32, font_size: f64) -> Unit {
+        (thousandths as f64 / 1000.0 * font_size * MM_PER_POINT).mm()
+    }
 }
 
 impl Default for FontProxy {
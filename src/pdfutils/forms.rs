@@ -0,0 +1,225 @@
+use crate::pdfutils::outline::text_string;
+use crate::pdfutils::PageParts;
+use crate::{Result, WRect};
+use printpdf::lopdf::{self, dictionary, Dictionary, Object, ObjectId, Stream};
+use printpdf::Pt;
+use std::collections::HashSet;
+
+/// The kinds of form field that can be filled in when the PDF is viewed.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FieldKind {
+    Checkbox,
+    Text,
+    // Text that wraps onto more than one line.
+    MultilineText,
+}
+
+/// A named form field, saved by PDF viewers along with the rest of the document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormField {
+    name: String,
+    kind: FieldKind,
+}
+
+impl FormField {
+    // Names must be unique within a document. A name that is used again, like on the second side
+    // of a double-sided page, has the page number added to it.
+    pub fn new(name: impl Into<String>, kind: FieldKind) -> FormField {
+        FormField {
+            name: name.into(),
+            kind,
+        }
+    }
+
+    pub fn checkbox(name: impl Into<String>) -> FormField {
+        FormField::new(name, FieldKind::Checkbox)
+    }
+
+    pub fn text(name: impl Into<String>) -> FormField {
+        FormField::new(name, FieldKind::Text)
+    }
+
+    pub fn multiline_text(name: impl Into<String>) -> FormField {
+        FormField::new(name, FieldKind::MultilineText)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn kind(&self) -> FieldKind {
+        self.kind
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Field {
+    // In page coordinates, after any transforms that were in effect when it was added.
+    pub(crate) rect: WRect,
+    pub(crate) field: FormField,
+}
+
+// The name of the font that text fields are filled in with, in the form's resources.
+const FIELD_FONT: &str = "Helv";
+// Auto-sized black text.
+const DEFAULT_APPEARANCE: &str = "/Helv 0 Tf 0 g";
+// Field flag for text fields that wrap.
+const MULTILINE_FLAG: i64 = 1 << 12;
+// Annotation flag that makes the field print.
+const PRINT_FLAG: i64 = 1 << 2;
+
+pub(crate) fn pdf_rect(rect: &WRect) -> Vec<Object> {
+    [rect.left(), rect.bottom_q1(), rect.right(), rect.top()]
+        .iter()
+        .map(|unit| Pt::from(unit).0.into())
+        .collect()
+}
+
+// The appearance of a checked box: a check mark in the middle of the field. It is drawn with
+// lines, so that it doesn't depend on the ZapfDingbats font being available.
+fn check_appearance(rect: &WRect) -> Stream {
+    let width = Pt::from(rect.width()).0;
+    let height = Pt::from(rect.height()).0;
+    let size = width.min(height) * 0.6;
+    let (x, y) = (width / 2.0, height / 2.0);
+    let content = format!(
+        "q 0 G {:.2} w 1 J 1 j {:.2} {:.2} m {:.2} {:.2} l {:.2} {:.2} l S Q",
+        size * 0.12,
+        x - size * 0.4,
+        y,
+        x - size * 0.1,
+        y - size * 0.35,
+        x + size * 0.4,
+        y + size * 0.4,
+    );
+    Stream::new(
+        dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Form",
+            "BBox" => vec![0.into(), 0.into(), width.into(), height.into()],
+        },
+        content.into_bytes(),
+    )
+}
+
+fn empty_appearance(rect: &WRect) -> Stream {
+    let width = Pt::from(rect.width()).0;
+    let height = Pt::from(rect.height()).0;
+    Stream::new(
+        dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Form",
+            "BBox" => vec![0.into(), 0.into(), width.into(), height.into()],
+        },
+        vec![],
+    )
+}
+
+// The field and its widget annotation, which PDF lets us merge into one dictionary.
+fn field_dictionary(
+    pdf: &mut lopdf::Document,
+    field: &Field,
+    name: &str,
+    page_id: ObjectId,
+) -> Dictionary {
+    let mut dict = dictionary! {
+        "Type" => "Annot",
+        "Subtype" => "Widget",
+        "T" => text_string(name),
+        "Rect" => pdf_rect(&field.rect),
+        "P" => page_id,
+        "F" => PRINT_FLAG,
+    };
+    match field.field.kind {
+        FieldKind::Checkbox => {
+            let on = pdf.add_object(check_appearance(&field.rect));
+            let off = pdf.add_object(empty_appearance(&field.rect));
+            dict.set("FT", "Btn");
+            dict.set("V", "Off");
+            dict.set("AS", "Off");
+            dict.set(
+                "AP",
+                dictionary! { "N" => dictionary! { "Yes" => on, "Off" => off } },
+            );
+        }
+        FieldKind::Text | FieldKind::MultilineText => {
+            dict.set("FT", "Tx");
+            dict.set("DA", Object::string_literal(DEFAULT_APPEARANCE));
+            if field.field.kind == FieldKind::MultilineText {
+                dict.set("Ff", MULTILINE_FLAG);
+            }
+        }
+    }
+    dict
+}
+
+// The first unused name of `name`, `name (page N)`, `name (page N, 2)`, ... A component that is
+// placed more than once can put the same field on a page several times.
+fn unique_name(name: &str, page: usize, names_used: &mut HashSet<String>) -> String {
+    let mut unique = name.to_string();
+    let mut count = 1;
+    while names_used.contains(&unique) {
+        unique = if count == 1 {
+            format!("{} (page {})", name, page)
+        } else {
+            format!("{} (page {}, {})", name, page, count)
+        };
+        count += 1;
+    }
+    names_used.insert(unique.clone());
+    unique
+}
+
+// Adds a widget annotation to its page for every field, and lists them all in the document's
+// interactive form.
+pub(crate) fn embed_fields(pdf: &mut lopdf::Document, pages: &[PageParts]) -> Result<()> {
+    if pages.iter().all(|page| page.fields.is_empty()) {
+        return Ok(());
+    }
+
+    let page_ids: Vec<ObjectId> = pdf.get_pages().values().cloned().collect();
+    let mut names_used = HashSet::new();
+    let mut all_fields: Vec<Object> = vec![];
+    for (index, (page, page_id)) in pages.iter().zip(&page_ids).enumerate() {
+        if page.fields.is_empty() {
+            continue;
+        }
+
+        let mut annotations = vec![];
+        for field in &page.fields {
+            let name = unique_name(&field.field.name, index + 1, &mut names_used);
+
+            let dict = field_dictionary(pdf, field, &name, *page_id);
+            let field_id = pdf.add_object(dict);
+            annotations.push(field_id.into());
+            all_fields.push(field_id.into());
+        }
+
+        // Links may already have added annotations to the page.
+        let page_dict = pdf.get_object_mut(*page_id)?.as_dict_mut()?;
+        if let Ok(Object::Array(existing)) = page_dict.get_mut(b"Annots") {
+            existing.append(&mut annotations);
+        } else {
+            page_dict.set("Annots", annotations);
+        }
+    }
+
+    let font_id = pdf.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+        "Encoding" => "WinAnsiEncoding",
+    });
+    let acro_form = dictionary! {
+        "Fields" => all_fields,
+        // Viewers draw the text fields themselves, so we only supply appearances for checkboxes.
+        "NeedAppearances" => true,
+        "DA" => Object::string_literal(DEFAULT_APPEARANCE),
+        "DR" => dictionary! { "Font" => dictionary! { FIELD_FONT => font_id } },
+    };
+    let catalog_id = pdf.trailer.get(b"Root")?.as_reference()?;
+    pdf.get_object_mut(catalog_id)?
+        .as_dict_mut()?
+        .set("AcroForm", acro_form);
+    Ok(())
+}
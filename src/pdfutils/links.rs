@@ -0,0 +1,104 @@
+use crate::pdfutils::PageParts;
+use crate::{Result, Transform, Unit, WRect, WeeklyError};
+use printpdf::lopdf::{self, dictionary, Object, ObjectId};
+use printpdf::Pt;
+use std::collections::BTreeMap;
+
+/// Where a link goes when it is clicked.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinkTarget {
+    // A page number, starting at 1, like `PageSetup::content_rect()`.
+    Page(usize),
+    // The page that called `Instructions::anchor()` with this name.
+    Anchor(String),
+}
+
+impl LinkTarget {
+    pub fn anchor(name: impl Into<String>) -> LinkTarget {
+        LinkTarget::Anchor(name.into())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Link {
+    // In page coordinates, after any transforms that were in effect when it was added.
+    pub(crate) rect: WRect,
+    pub(crate) target: LinkTarget,
+}
+
+// The smallest rect containing `rect` after it has been transformed.
+pub(crate) fn transform_rect(transform: &Transform, rect: &WRect) -> WRect {
+    let corners = [
+        transform.apply(rect.left(), rect.top()),
+        transform.apply(rect.right(), rect.top()),
+        transform.apply(rect.left(), rect.bottom_q1()),
+        transform.apply(rect.right(), rect.bottom_q1()),
+    ];
+    // unwrap: there are always four corners.
+    let xs = corners.iter().map(|(x, _)| *x);
+    let ys = corners.iter().map(|(_, y)| *y);
+    let left = xs.clone().reduce(Unit::min).unwrap();
+    let right = xs.reduce(Unit::max).unwrap();
+    let bottom = ys.clone().reduce(Unit::min).unwrap();
+    let top = ys.reduce(Unit::max).unwrap();
+    WRect::with_dimensions(right - left, top - bottom).move_to(left, top)
+}
+
+// Resolves a target to the index of its page, starting at 0.
+pub(crate) fn resolve_target(
+    target: &LinkTarget,
+    pages: &[PageParts],
+    num_pages: usize,
+) -> Result<usize> {
+    match target {
+        LinkTarget::Page(page) if *page >= 1 && *page <= num_pages => Ok(page - 1),
+        LinkTarget::Page(page) => Err(WeeklyError::UnknownLinkTarget(format!("page {}", page))),
+        LinkTarget::Anchor(name) => pages
+            .iter()
+            .position(|page| page.anchors.contains(name))
+            .ok_or_else(|| WeeklyError::UnknownLinkTarget(name.clone())),
+    }
+}
+
+// A destination that shows the whole of the page.
+pub(crate) fn page_destination(page_id: ObjectId) -> Object {
+    Object::Array(vec![page_id.into(), "Fit".into()])
+}
+
+// Adds a link annotation to its page for every link.
+pub(crate) fn embed_links(pdf: &mut lopdf::Document, pages: &[PageParts]) -> Result<()> {
+    let page_ids: BTreeMap<u32, ObjectId> = pdf.get_pages();
+    let page_ids: Vec<ObjectId> = page_ids.values().cloned().collect();
+
+    for (page, page_id) in pages.iter().zip(&page_ids) {
+        if page.links.is_empty() {
+            continue;
+        }
+
+        let mut annotations = vec![];
+        for link in &page.links {
+            let target = resolve_target(&link.target, pages, page_ids.len())?;
+            let rect: Vec<Object> = [
+                link.rect.left(),
+                link.rect.bottom_q1(),
+                link.rect.right(),
+                link.rect.top(),
+            ]
+            .iter()
+            .map(|unit| Pt::from(unit).0.into())
+            .collect();
+            let annotation = pdf.add_object(dictionary! {
+                "Type" => "Annot",
+                "Subtype" => "Link",
+                "Rect" => rect,
+                "Border" => vec![0.into(), 0.into(), 0.into()],
+                "Dest" => page_destination(page_ids[target]),
+            });
+            annotations.push(annotation.into());
+        }
+        pdf.get_object_mut(*page_id)?
+            .as_dict_mut()?
+            .set("Annots", annotations);
+    }
+    Ok(())
+}
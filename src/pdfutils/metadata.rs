@@ -0,0 +1,123 @@
+use crate::pdfutils::fnv::FnvHasher;
+use printpdf::{OffsetDateTime, PdfDocumentReference};
+use std::hash::Hasher;
+
+/// The information about a document that PDF viewers show in its properties.
+///
+/// The save functions take anything that converts into this, so a plain title works too.
+///
+/// A reproducible document gets the same timestamps and ID every time it is written, so the same
+/// instructions always produce the same bytes. Setting the `SOURCE_DATE_EPOCH` environment
+/// variable makes every document reproducible, with that time as its timestamp.
+#[derive(Debug, Clone, Default)]
+pub struct DocumentMetadata {
+    title: String,
+    author: Option<String>,
+    subject: Option<String>,
+    keywords: Vec<String>,
+    creator: Option<String>,
+    reproducible: bool,
+}
+
+impl DocumentMetadata {
+    pub fn new(title: impl Into<String>) -> DocumentMetadata {
+        DocumentMetadata {
+            title: title.into(),
+            reproducible: source_date_epoch().is_some(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_author(mut self, author: impl Into<String>) -> Self {
+        self.author = Some(author.into());
+        self
+    }
+
+    pub fn with_subject(mut self, subject: impl Into<String>) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+
+    pub fn with_keywords(mut self, keywords: &[impl AsRef<str>]) -> Self {
+        self.keywords = keywords.iter().map(|k| k.as_ref().to_string()).collect();
+        self
+    }
+
+    // The program that made the document. Defaults to this library.
+    pub fn with_creator(mut self, creator: impl Into<String>) -> Self {
+        self.creator = Some(creator.into());
+        self
+    }
+
+    pub fn with_reproducible(mut self, reproducible: bool) -> Self {
+        self.reproducible = reproducible;
+        self
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn is_reproducible(&self) -> bool {
+        self.reproducible
+    }
+
+    // Sets everything except the title, which the document is created with.
+    pub(crate) fn apply(&self, doc: PdfDocumentReference) -> PdfDocumentReference {
+        let creator = self
+            .creator
+            .clone()
+            .unwrap_or_else(|| format!("weekly {}", env!("CARGO_PKG_VERSION")));
+        let mut doc = doc
+            .with_author(self.author.clone().unwrap_or_default())
+            .with_subject(self.subject.clone().unwrap_or_default())
+            .with_keywords(self.keywords.clone())
+            .with_creator(creator)
+            .with_producer("printpdf");
+
+        if self.reproducible {
+            let timestamp = OffsetDateTime::from_unix_timestamp(source_date_epoch().unwrap_or(0));
+            doc = doc
+                .with_creation_date(timestamp)
+                .with_mod_date(timestamp)
+                .with_metadata_date(timestamp);
+        }
+        doc
+    }
+}
+
+impl From<&str> for DocumentMetadata {
+    fn from(title: &str) -> Self {
+        DocumentMetadata::new(title)
+    }
+}
+
+impl From<&String> for DocumentMetadata {
+    fn from(title: &String) -> Self {
+        DocumentMetadata::new(title.as_str())
+    }
+}
+
+impl From<String> for DocumentMetadata {
+    fn from(title: String) -> Self {
+        DocumentMetadata::new(title)
+    }
+}
+
+// The time to use for reproducible builds, in seconds since 1970, following
+// https://reproducible-builds.org/specs/source-date-epoch/.
+fn source_date_epoch() -> Option<i64> {
+    std::env::var("SOURCE_DATE_EPOCH").ok()?.trim().parse().ok()
+}
+
+// A document ID made from the bytes of the document, in the 16 bytes that PDF writers usually use.
+pub(crate) fn content_id(bytes: &[u8]) -> Vec<u8> {
+    let mut id = vec![];
+    for seed in 0..2u8 {
+        let mut hasher = FnvHasher::default();
+        hasher.write_u8(seed);
+        hasher.write(bytes);
+        id.extend_from_slice(&hasher.finish().to_be_bytes());
+    }
+    id
+}
@@ -1,18 +1,40 @@
+mod component;
+mod fnv;
 mod font_map;
+mod font_metrics;
 mod font_proxy;
+mod forms;
+mod links;
+mod metadata;
+mod outline;
+mod page_setup;
 pub mod sizes;
 mod text_context;
+mod transform;
 
 use crate::units::Unit;
 use crate::{Result, ToPdfLine, WRect};
+use component::embed_components;
 use font_map::FontMap;
 pub use font_proxy::FontProxy;
+use forms::{embed_fields, Field};
+use links::{embed_links, transform_rect, Link};
+use metadata::content_id;
+use outline::embed_outline;
+use page_setup::{embed_page_boxes, PageBoxes};
+use printpdf::lopdf::content::Operation;
 use printpdf::*;
-use std::fs::File;
-use std::io::BufWriter;
+use std::fs;
+use std::io::{self, BufWriter, Write};
 use std::path::Path;
 
+pub use component::Component;
+pub use forms::{FieldKind, FormField};
+pub use links::LinkTarget;
+pub use metadata::DocumentMetadata;
+pub use page_setup::{Margins, PageSetup};
 pub use text_context::TextContext;
+pub use transform::Transform;
 
 pub fn point_pair(x: Unit, y: Unit, next: bool) -> (Point, bool) {
     (Point::new(x.into(), y.into()), next)
@@ -37,11 +59,35 @@ impl Instructions {
     }
 
     pub fn rotate(&mut self, deg: f64) {
-        self.instructions.push(Instruction::Rotate(deg));
+        self.transform(&Transform::rotate(deg));
     }
 
     pub fn translate(&mut self, x: Unit, y: Unit) {
-        self.instructions.push(Instruction::Translate(x, y));
+        self.transform(&Transform::translate(x, y));
+    }
+
+    // Applies `transform` to everything drawn after it, until the state is popped.
+    pub fn transform(&mut self, transform: &Transform) {
+        self.instructions.push(Instruction::Transform(*transform));
+    }
+
+    // Draws `component` with `transform` applied to it. Its links and fields are added at every
+    // place that it is drawn.
+    pub fn place(&mut self, component: &Component, transform: &Transform) {
+        self.instructions
+            .push(Instruction::Place(component.clone(), *transform));
+    }
+
+    pub fn place_at(&mut self, component: &Component, x: Unit, y: Unit) {
+        self.place(component, &Transform::translate(x, y));
+    }
+
+    // Draws the instructions added by `f` with `transform` applied to them.
+    pub fn with_transform(&mut self, transform: &Transform, f: impl FnOnce(&mut Instructions)) {
+        self.push_state();
+        self.transform(transform);
+        f(self);
+        self.pop_state();
     }
 
     pub fn push_shape(&mut self, shape: impl ToPdfLine) {
@@ -49,6 +95,43 @@ impl Instructions {
             .push(Instruction::Shape(shape.to_pdf_line()))
     }
 
+    // Limits everything drawn after this to the inside of `shape`, until the state is popped.
+    pub fn clip_to(&mut self, shape: impl ToPdfLine) {
+        let mut line = shape.to_pdf_line();
+        line.set_as_clipping_path(true);
+        self.instructions.push(Instruction::Shape(line))
+    }
+
+    // Makes `rect` a link to `target` when the PDF is viewed. Nothing is drawn.
+    pub fn link(&mut self, rect: &WRect, target: LinkTarget) {
+        self.instructions
+            .push(Instruction::Link(rect.clone(), target));
+    }
+
+    // Names the page that these instructions are drawn on, so that links can go to it with
+    // `LinkTarget::Anchor`.
+    pub fn anchor(&mut self, name: impl Into<String>) {
+        self.instructions.push(Instruction::Anchor(name.into()));
+    }
+
+    // Adds the page that these instructions are drawn on to the document outline. The path runs
+    // from the top of the outline down, like `["2026", "October", "Week 42"]`. Pages that share
+    // the start of a path share those bookmarks, which go to the first of the pages.
+    pub fn bookmark(&mut self, path: &[impl AsRef<str>]) {
+        self.instructions.push(Instruction::Bookmark(
+            path.iter()
+                .map(|title| title.as_ref().to_string())
+                .collect(),
+        ));
+    }
+
+    // Adds a form field over `rect`, which can be filled in when the PDF is viewed. Nothing is
+    // drawn, so the page should show where the field is.
+    pub fn field(&mut self, rect: &WRect, field: FormField) {
+        self.instructions
+            .push(Instruction::Field(rect.clone(), field));
+    }
+
     pub fn push_text(&mut self, s: &str, text_height: f64, x: Unit, y: Unit, font: FontProxy) {
         self.instructions.push(Instruction::Text(TextValues {
             s: s.to_string(),
@@ -89,7 +172,7 @@ impl Instructions {
                 .push(Instruction::Attrs(Attributes::default()));
         }
         // unwrap: The last three lines ensure that an Attrs is last in the instructions list.
-        return self.instructions.last_mut().unwrap().attrs_mut().unwrap();
+        self.instructions.last_mut().unwrap().attrs_mut().unwrap()
     }
 
     pub fn draw_to_layer(
@@ -98,13 +181,68 @@ impl Instructions {
         layer: &PdfLayerReference,
     ) -> Result<()> {
         // TODO: should we ensure that the document and layer are consistent?
-        let font_map = FontMap::default().resolve_fonts(document, self)?;
+        FontMap::default().resolve_fonts(document, self)?;
 
-        for instruction in &self.instructions {
-            instruction.draw_to_layer(layer, &font_map);
+        for operation in self.operations() {
+            layer.add_operation(operation);
         }
         Ok(())
     }
+
+    fn operations(&self) -> Vec<Operation> {
+        self.instructions
+            .iter()
+            .flat_map(Instruction::operations)
+            .collect()
+    }
+
+    // The links, anchors, bookmarks and fields in these instructions, and in the components that
+    // they place, with the rects moved by `transform` and by any transforms in the instructions.
+    fn collect_page_parts(&self, transform: &Transform) -> PageParts {
+        let mut page_parts = PageParts::default();
+        self.add_page_parts(transform, &mut page_parts);
+        page_parts
+    }
+
+    fn add_page_parts(&self, transform: &Transform, page_parts: &mut PageParts) {
+        let mut current = *transform;
+        let mut saved = vec![];
+        for instruction in &self.instructions {
+            match instruction {
+                Instruction::PushState => saved.push(current),
+                Instruction::PopState => current = saved.pop().unwrap_or(*transform),
+                Instruction::Transform(t) => current = t.then(&current),
+                // Every placement of a component gets its own links and fields.
+                Instruction::Place(component, t) => component
+                    .instructions()
+                    .add_page_parts(&t.then(&current), page_parts),
+                Instruction::Link(rect, target) => page_parts.links.push(Link {
+                    rect: transform_rect(&current, rect),
+                    target: target.clone(),
+                }),
+                Instruction::Anchor(name) => page_parts.anchors.push(name.clone()),
+                Instruction::Bookmark(path) => page_parts.bookmarks.push(path.clone()),
+                Instruction::Field(rect, field) => page_parts.fields.push(Field {
+                    rect: transform_rect(&current, rect),
+                    field: field.clone(),
+                }),
+                _ => {}
+            }
+        }
+    }
+
+    // Adds every component placed by these instructions to `components`, including those placed
+    // by the components themselves.
+    fn collect_components(&self, components: &mut Vec<Component>) {
+        for instruction in &self.instructions {
+            if let Instruction::Place(component, _) = instruction {
+                if !components.iter().any(|c| c.name() == component.name()) {
+                    components.push(component.clone());
+                    component.instructions().collect_components(components);
+                }
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -115,8 +253,14 @@ pub enum Instruction {
 
     PushState,
     PopState,
-    Rotate(f64),
-    Translate(Unit, Unit),
+    Transform(Transform),
+    Place(Component, Transform),
+
+    // These are written to the PDF after the page contents.
+    Link(WRect, LinkTarget),
+    Anchor(String),
+    Bookmark(Vec<String>),
+    Field(WRect, FormField),
 }
 
 impl Instruction {
@@ -127,28 +271,31 @@ impl Instruction {
         }
     }
 
-    fn draw_to_layer(&self, layer: &PdfLayerReference, font_map: &FontMap) {
+    // The PDF content stream operations for this instruction. These are the same operations that
+    // the PdfLayerReference methods would add, but they can also be used outside of a page.
+    fn operations(&self) -> Vec<Operation> {
         match self {
-            Instruction::Shape(line) => layer.add_shape(line.clone()),
-            Instruction::Attrs(attrs) => attrs.execute_in_layer(layer),
-            Instruction::Text(txt) => layer.use_text(
-                txt.s.clone(),
-                txt.text_height,
-                txt.x.into(),
-                txt.y.into(),
-                font_map.lookup(txt.font),
-            ),
-            Instruction::PushState => layer.save_graphics_state(),
-            Instruction::PopState => layer.restore_graphics_state(),
-            Instruction::Rotate(r) => layer.set_ctm(CurTransMat::Rotate(*r)),
-            Instruction::Translate(x, y) => {
-                layer.set_ctm(CurTransMat::Translate(x.into(), y.into()));
-            }
-        };
+            Instruction::Shape(line) => line.clone().into_stream_op(),
+            Instruction::Attrs(attrs) => attrs.operations(),
+            Instruction::Text(txt) => txt.operations(),
+            Instruction::PushState => vec![Operation::new("q", vec![])],
+            Instruction::PopState => vec![Operation::new("Q", vec![])],
+            Instruction::Transform(transform) => vec![transform.to_ctm().into()],
+            Instruction::Place(component, transform) => vec![
+                Operation::new("q", vec![]),
+                transform.to_ctm().into(),
+                Operation::new("Do", vec![component.name().into()]),
+                Operation::new("Q", vec![]),
+            ],
+            Instruction::Link(_, _)
+            | Instruction::Anchor(_)
+            | Instruction::Bookmark(_)
+            | Instruction::Field(_, _) => vec![],
+        }
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Attributes {
     stroke_width: Option<f64>,
     stroke_color: Option<Color>,
@@ -167,6 +314,11 @@ impl Attributes {
         self
     }
 
+    pub fn with_fill_color(mut self, color: &Color) -> Self {
+        self.fill_color = Some(color.clone());
+        self
+    }
+
     pub fn with_dash(mut self, dash: i64, gap: i64) -> Self {
         self.dash = Some((Some(dash), gap));
         self
@@ -205,27 +357,26 @@ impl Attributes {
         }
     }
 
-    fn execute_in_layer(&self, layer: &PdfLayerReference) {
+    fn operations(&self) -> Vec<Operation> {
+        let mut operations = vec![];
         if let Some(stroke_width) = &self.stroke_width {
-            layer.set_outline_thickness(*stroke_width);
+            operations.push(Operation::new(
+                OP_PATH_STATE_SET_LINE_WIDTH,
+                vec![(*stroke_width).into()],
+            ));
         }
         if let Some(stroke_color) = &self.stroke_color {
-            layer.set_outline_color(stroke_color.clone());
+            operations.push(PdfColor::OutlineColor(stroke_color.clone()).into());
         }
         if let Some(fill_color) = &self.fill_color {
-            layer.set_fill_color(fill_color.clone());
+            operations.push(PdfColor::FillColor(fill_color.clone()).into());
         }
         if let Some((dash_len, gap)) = &self.dash {
-            layer.set_line_dash_pattern(LineDashPattern::new(
-                0,
-                *dash_len,
-                Some(*gap),
-                None,
-                None,
-                None,
-                None,
-            ));
+            operations.push(
+                LineDashPattern::new(0, *dash_len, Some(*gap), None, None, None, None).into(),
+            );
         }
+        operations
     }
 }
 
@@ -238,6 +389,30 @@ pub struct TextValues {
     font: FontProxy,
 }
 
+impl TextValues {
+    fn operations(&self) -> Vec<Operation> {
+        // Built-in fonts are added to the document's resources under their own names,
+        // using the WinAnsiEncoding.
+        let font_name: &'static str = BuiltinFont::from(self.font).into();
+        let x: Pt = self.x.into();
+        let y: Pt = self.y.into();
+        let bytes = lopdf::Document::encode_text(Some("WinAnsiEncoding"), &self.s);
+        vec![
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec![font_name.into(), self.text_height.into()]),
+            Operation::new("Td", vec![x.into(), y.into()]),
+            Operation::new(
+                "Tj",
+                vec![lopdf::Object::String(
+                    bytes,
+                    lopdf::StringFormat::Hexadecimal,
+                )],
+            ),
+            Operation::new("ET", vec![]),
+        ]
+    }
+}
+
 pub struct Colors {}
 
 impl Colors {
@@ -270,31 +445,115 @@ impl Colors {
     }
 }
 
-pub fn save_one_page_document<F>(
-    title: &str,
-    filename: impl AsRef<Path>,
+// The write_ functions write the PDF to any writer, like a `Vec<u8>` or a socket. The save_
+// functions write it to a file, or to stdout if the filename is "-".
+
+pub fn write_one_page_document<F>(
+    metadata: impl Into<DocumentMetadata>,
+    writer: impl Write,
     page_bounds: &WRect,
     callback: F,
 ) -> Result<()>
 where
     F: FnOnce(&PdfDocumentReference, &WRect) -> Result<Instructions>,
 {
+    let metadata = metadata.into();
     let (doc, page, layer) = PdfDocument::new(
-        title,
+        metadata.title(),
         page_bounds.width().into(),
         page_bounds.height().into(),
         "Layer 1",
     );
+    let doc = metadata.apply(doc);
 
-    callback(&doc, page_bounds)?.draw_to_layer(&doc, &doc.get_page(page).get_layer(layer))?;
+    let instructions = callback(&doc, page_bounds)?;
+    instructions.draw_to_layer(&doc, &doc.get_page(page).get_layer(layer))?;
 
-    doc.save(&mut BufWriter::new(File::create(filename)?))?;
-    Ok(())
+    let mut parts = DocumentParts::default();
+    parts.add_page(&instructions, &Transform::identity());
+    write_pdf(doc, &metadata, &parts, writer)
 }
 
-pub fn save_double_sided_document<F>(
-    title: &str,
-    filename: impl AsRef<Path>,
+pub fn write_multi_page_document<F>(
+    metadata: impl Into<DocumentMetadata>,
+    writer: impl Write,
+    page_bounds: &WRect,
+    callback: F,
+) -> Result<()>
+where
+    F: FnOnce(&PdfDocumentReference, &WRect) -> Result<Vec<Instructions>>,
+{
+    write_pages(metadata, writer, page_bounds, None, callback)
+}
+
+// Writes a page for each of the callback's instructions, giving every page `page_boxes`.
+fn write_pages<F>(
+    metadata: impl Into<DocumentMetadata>,
+    writer: impl Write,
+    page_bounds: &WRect,
+    page_boxes: Option<PageBoxes>,
+    callback: F,
+) -> Result<()>
+where
+    F: FnOnce(&PdfDocumentReference, &WRect) -> Result<Vec<Instructions>>,
+{
+    let metadata = metadata.into();
+    let (doc, page, layer) = PdfDocument::new(
+        metadata.title(),
+        page_bounds.width().into(),
+        page_bounds.height().into(),
+        "Layer 1",
+    );
+    let doc = metadata.apply(doc);
+
+    let mut parts = DocumentParts {
+        page_boxes,
+        ..DocumentParts::default()
+    };
+    for (index, instructions) in callback(&doc, page_bounds)?.iter().enumerate() {
+        // The document is created with its first page, so only add pages after that.
+        let layer_ref = if index == 0 {
+            doc.get_page(page).get_layer(layer)
+        } else {
+            let (page, layer) = doc.add_page(
+                page_bounds.width().into(),
+                page_bounds.height().into(),
+                "Layer 1",
+            );
+            doc.get_page(page).get_layer(layer)
+        };
+        instructions.draw_to_layer(&doc, &layer_ref)?;
+        parts.add_page(instructions, &Transform::identity());
+    }
+
+    write_pdf(doc, &metadata, &parts, writer)
+}
+
+/// Writes a document whose pages are laid out by `page_setup`. The callback returns the
+/// instructions for each page, using `PageSetup::content_rect()` to find where to draw.
+pub fn write_document<F>(
+    metadata: impl Into<DocumentMetadata>,
+    writer: impl Write,
+    page_setup: &PageSetup,
+    callback: F,
+) -> Result<()>
+where
+    F: FnOnce(&PdfDocumentReference, &PageSetup) -> Result<Vec<Instructions>>,
+{
+    let media_rect = page_setup.media_rect();
+    let page_boxes = page_setup.page_boxes();
+    write_pages(metadata, writer, &media_rect, page_boxes, |doc, _| {
+        let mut pages = callback(doc, page_setup)?;
+        for (index, instructions) in pages.iter_mut().enumerate() {
+            instructions.append(page_setup.decorations(index + 1));
+        }
+        Ok(pages)
+    })
+}
+
+pub fn write_double_sided_document<F>(
+    metadata: impl Into<DocumentMetadata>,
+    writer: impl Write,
     page_bounds: &WRect,
     flip_page_2: bool,
     callback: F,
@@ -302,12 +561,14 @@ pub fn save_double_sided_document<F>(
 where
     F: FnOnce(&PdfDocumentReference, &WRect) -> Result<Instructions>,
 {
+    let metadata = metadata.into();
     let (doc, page, layer) = PdfDocument::new(
-        title,
+        metadata.title(),
         page_bounds.width().into(),
         page_bounds.height().into(),
         "Layer 1",
     );
+    let doc = metadata.apply(doc);
 
     let instructions = callback(&doc, page_bounds)?;
     instructions.draw_to_layer(&doc, &doc.get_page(page).get_layer(layer))?;
@@ -321,15 +582,167 @@ where
     let layer1_ref = &doc.get_page(page2).get_layer(layer1);
     layer1_ref.save_graphics_state();
 
-    if flip_page_2 {
-        let width_mm: Mm = page_bounds.width().into();
-        let height_mm: Mm = page_bounds.height().into();
-        let mat = CurTransMat::TranslateRotate(width_mm.into_pt(), height_mm.into_pt(), 180.0);
-        layer1_ref.set_ctm(mat);
-    }
+    let page_2_transform = if flip_page_2 {
+        Transform::rotate_about(
+            180.0,
+            page_bounds.left() + page_bounds.width() / 2,
+            page_bounds.bottom_q1() + page_bounds.height() / 2,
+        )
+    } else {
+        Transform::identity()
+    };
+    layer1_ref.set_ctm(page_2_transform.to_ctm());
     instructions.draw_to_layer(&doc, layer1_ref)?;
     layer1_ref.restore_graphics_state();
 
-    doc.save(&mut BufWriter::new(File::create(filename)?))
-        .map_err(|e| e.into())
+    let mut parts = DocumentParts::default();
+    parts.add_page(&instructions, &Transform::identity());
+    parts.add_page(&instructions, &page_2_transform);
+    write_pdf(doc, &metadata, &parts, writer)
+}
+
+pub fn save_one_page_document<F>(
+    metadata: impl Into<DocumentMetadata>,
+    filename: impl AsRef<Path>,
+    page_bounds: &WRect,
+    callback: F,
+) -> Result<()>
+where
+    F: FnOnce(&PdfDocumentReference, &WRect) -> Result<Instructions>,
+{
+    save_output(filename, |writer| {
+        write_one_page_document(metadata, writer, page_bounds, callback)
+    })
+}
+
+pub fn save_multi_page_document<F>(
+    metadata: impl Into<DocumentMetadata>,
+    filename: impl AsRef<Path>,
+    page_bounds: &WRect,
+    callback: F,
+) -> Result<()>
+where
+    F: FnOnce(&PdfDocumentReference, &WRect) -> Result<Vec<Instructions>>,
+{
+    save_output(filename, |writer| {
+        write_multi_page_document(metadata, writer, page_bounds, callback)
+    })
+}
+
+/// Saves a document whose pages are laid out by `page_setup`, like `write_document()`.
+pub fn save_document<F>(
+    metadata: impl Into<DocumentMetadata>,
+    filename: impl AsRef<Path>,
+    page_setup: &PageSetup,
+    callback: F,
+) -> Result<()>
+where
+    F: FnOnce(&PdfDocumentReference, &PageSetup) -> Result<Vec<Instructions>>,
+{
+    save_output(filename, |writer| {
+        write_document(metadata, writer, page_setup, callback)
+    })
+}
+
+pub fn save_double_sided_document<F>(
+    metadata: impl Into<DocumentMetadata>,
+    filename: impl AsRef<Path>,
+    page_bounds: &WRect,
+    flip_page_2: bool,
+    callback: F,
+) -> Result<()>
+where
+    F: FnOnce(&PdfDocumentReference, &WRect) -> Result<Instructions>,
+{
+    save_output(filename, |writer| {
+        write_double_sided_document(metadata, writer, page_bounds, flip_page_2, callback)
+    })
+}
+
+// Writes the document to a file, or to stdout for "-". The whole document is written to memory
+// first, so that a failure while rendering leaves any earlier file in place.
+fn save_output(
+    filename: impl AsRef<Path>,
+    write: impl FnOnce(&mut Vec<u8>) -> Result<()>,
+) -> Result<()> {
+    let mut bytes = vec![];
+    write(&mut bytes)?;
+    if filename.as_ref() == Path::new("-") {
+        let mut stdout = io::stdout();
+        stdout.write_all(&bytes)?;
+        stdout.flush()?;
+    } else {
+        fs::write(filename, bytes)?;
+    }
+    Ok(())
+}
+
+// The links, anchors, bookmarks and fields found on one page.
+#[derive(Debug, Default)]
+pub(crate) struct PageParts {
+    pub(crate) links: Vec<Link>,
+    pub(crate) anchors: Vec<String>,
+    pub(crate) bookmarks: Vec<Vec<String>>,
+    pub(crate) fields: Vec<Field>,
+}
+
+// The parts of a document that printpdf doesn't know how to write, gathered from the
+// instructions for each page.
+#[derive(Debug, Default)]
+struct DocumentParts {
+    components: Vec<Component>,
+    pages: Vec<PageParts>,
+    page_boxes: Option<PageBoxes>,
+}
+
+impl DocumentParts {
+    // `transform` is applied to the whole page when it is drawn.
+    fn add_page(&mut self, instructions: &Instructions, transform: &Transform) {
+        instructions.collect_components(&mut self.components);
+        self.pages.push(instructions.collect_page_parts(transform));
+    }
+
+    fn is_empty(&self) -> bool {
+        self.page_boxes.is_none()
+            && self.components.is_empty()
+            && self.pages.iter().all(|page| {
+                page.links.is_empty() && page.bookmarks.is_empty() && page.fields.is_empty()
+            })
+    }
+}
+
+// Writes the document to `writer`, adding the parts that printpdf doesn't know how to write.
+fn write_pdf(
+    doc: PdfDocumentReference,
+    metadata: &DocumentMetadata,
+    parts: &DocumentParts,
+    writer: impl Write,
+) -> Result<()> {
+    let mut writer = BufWriter::new(writer);
+    if parts.is_empty() && !metadata.is_reproducible() {
+        writer.write_all(&doc.save_to_bytes()?)?;
+        writer.flush()?;
+        return Ok(());
+    }
+
+    let mut pdf = lopdf::Document::load_mem(&doc.save_to_bytes()?)?;
+    embed_components(&mut pdf, &parts.components)?;
+    embed_links(&mut pdf, &parts.pages)?;
+    embed_outline(&mut pdf, &parts.pages)?;
+    embed_fields(&mut pdf, &parts.pages)?;
+    if let Some(page_boxes) = &parts.page_boxes {
+        embed_page_boxes(&mut pdf, page_boxes)?;
+    }
+
+    if metadata.is_reproducible() {
+        // printpdf makes up a random ID, so replace it with one made from everything else.
+        pdf.trailer.remove(b"ID");
+        let mut bytes = vec![];
+        pdf.save_to(&mut bytes)?;
+        let id = lopdf::Object::String(content_id(&bytes), lopdf::StringFormat::Hexadecimal);
+        pdf.trailer.set("ID", vec![id.clone(), id]);
+    }
+    pdf.save_to(&mut writer)?;
+    writer.flush()?;
+    Ok(())
 }
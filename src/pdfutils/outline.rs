@@ -0,0 +1,115 @@
+use crate::pdfutils::links::page_destination;
+use crate::pdfutils::PageParts;
+use crate::Result;
+use printpdf::lopdf::{self, dictionary, Dictionary, Object, ObjectId, StringFormat};
+
+// A bookmark in the PDF outline, which goes to the first page that was bookmarked with its path.
+#[derive(Debug)]
+struct OutlineItem {
+    title: String,
+    page: usize,
+    children: Vec<OutlineItem>,
+}
+
+// Merges the bookmark paths of every page into a tree, keeping the order of the pages.
+fn build_tree(pages: &[PageParts]) -> Vec<OutlineItem> {
+    let mut roots: Vec<OutlineItem> = vec![];
+    for (page, page_parts) in pages.iter().enumerate() {
+        for path in &page_parts.bookmarks {
+            let mut siblings = &mut roots;
+            for title in path {
+                let index = match siblings.iter().position(|item| &item.title == title) {
+                    Some(index) => index,
+                    None => {
+                        siblings.push(OutlineItem {
+                            title: title.clone(),
+                            page,
+                            children: vec![],
+                        });
+                        siblings.len() - 1
+                    }
+                };
+                siblings = &mut siblings[index].children;
+            }
+        }
+    }
+    roots
+}
+
+// PDF text strings are either PDFDocEncoding, which is ASCII-compatible, or UTF-16BE.
+pub(crate) fn text_string(s: &str) -> Object {
+    if s.is_ascii() {
+        Object::string_literal(s)
+    } else {
+        let mut bytes = vec![0xfe, 0xff];
+        for unit in s.encode_utf16() {
+            bytes.extend_from_slice(&unit.to_be_bytes());
+        }
+        Object::String(bytes, StringFormat::Hexadecimal)
+    }
+}
+
+// Adds `items` to the document as the children of `parent`, and returns their ids.
+fn add_items(
+    pdf: &mut lopdf::Document,
+    items: &[OutlineItem],
+    parent: ObjectId,
+    page_ids: &[ObjectId],
+) -> Vec<ObjectId> {
+    let ids: Vec<ObjectId> = items.iter().map(|_| pdf.new_object_id()).collect();
+    for (index, item) in items.iter().enumerate() {
+        let mut dict = dictionary! {
+            "Title" => text_string(&item.title),
+            "Parent" => parent,
+            "Dest" => page_destination(page_ids[item.page]),
+        };
+        if index > 0 {
+            dict.set("Prev", ids[index - 1]);
+        }
+        if index + 1 < ids.len() {
+            dict.set("Next", ids[index + 1]);
+        }
+
+        let children = add_items(pdf, &item.children, ids[index], page_ids);
+        if let (Some(first), Some(last)) = (children.first(), children.last()) {
+            dict.set("First", *first);
+            dict.set("Last", *last);
+            // Negative, so that the children start out hidden.
+            dict.set("Count", -(children.len() as i64));
+        }
+        pdf.objects.insert(ids[index], Object::Dictionary(dict));
+    }
+    ids
+}
+
+// Writes the outline built from the pages' bookmarks, and tells viewers to show it.
+pub(crate) fn embed_outline(pdf: &mut lopdf::Document, pages: &[PageParts]) -> Result<()> {
+    let tree = build_tree(pages);
+    if tree.is_empty() {
+        return Ok(());
+    }
+
+    let page_ids: Vec<ObjectId> = pdf.get_pages().values().cloned().collect();
+    let catalog_id = pdf.trailer.get(b"Root")?.as_reference()?;
+
+    // printpdf writes an empty outline, which we fill in.
+    let outlines_id = match pdf.get_dictionary(catalog_id)?.get(b"Outlines") {
+        Ok(Object::Reference(id)) => *id,
+        _ => pdf.new_object_id(),
+    };
+    let items = add_items(pdf, &tree, outlines_id, &page_ids);
+
+    let mut outlines = Dictionary::new();
+    outlines.set("Type", "Outlines");
+    // unwrap: tree is not empty, so neither is items.
+    outlines.set("First", *items.first().unwrap());
+    outlines.set("Last", *items.last().unwrap());
+    outlines.set("Count", items.len() as i64);
+    pdf.objects
+        .insert(outlines_id, Object::Dictionary(outlines));
+
+    let catalog = pdf.get_object_mut(catalog_id)?.as_dict_mut()?;
+    catalog.set("Outlines", outlines_id);
+    catalog.set("PageMode", "UseOutlines");
+    Ok(())
+}
@@ -0,0 +1,256 @@
+use crate::pdfutils::forms::pdf_rect;
+use crate::{Colors, Frame, HasRenderAttrs, Instructions, NumericUnit, Result, Unit, WLine, WRect};
+use printpdf::lopdf;
+
+/// Distances in from each edge of a rect.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Margins {
+    pub left: Unit,
+    pub top: Unit,
+    pub right: Unit,
+    pub bottom: Unit,
+}
+
+impl Margins {
+    pub fn new(left: Unit, top: Unit, right: Unit, bottom: Unit) -> Margins {
+        Margins {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    pub fn all(margin: Unit) -> Margins {
+        Margins::new(margin, margin, margin, margin)
+    }
+
+    // The larger of each of the four margins.
+    pub fn max(&self, other: &Margins) -> Margins {
+        Margins::new(
+            self.left.max(other.left),
+            self.top.max(other.top),
+            self.right.max(other.right),
+            self.bottom.max(other.bottom),
+        )
+    }
+
+    pub fn inset(&self, rect: &WRect) -> WRect {
+        rect.inset_all_q1(self.left, self.top, self.right, self.bottom)
+    }
+}
+
+/// Describes the physical page that a sheet is printed on.
+///
+/// The trim is the size of the finished page. The PDF page (the media) is larger than the trim
+/// when there is bleed or there are crop marks. All of the rects returned are in the coordinates
+/// of the media, so they can be used directly when rendering.
+///
+/// Page numbers start at 1. Odd pages are right-hand pages, so the binding gutter is added to
+/// their left margin. Even pages have the gutter on the right.
+#[derive(Debug, Clone)]
+pub struct PageSetup {
+    trim_size: WRect,
+    margins: Margins,
+    binding_gutter: Unit,
+    bleed: Unit,
+    non_printable: Margins,
+    crop_marks: bool,
+    debug_overlay: bool,
+}
+
+impl PageSetup {
+    const CROP_MARK_LENGTH: f64 = 0.25;
+    const CROP_MARK_OFFSET: f64 = 0.125;
+
+    pub fn new(trim_size: &WRect) -> PageSetup {
+        PageSetup {
+            trim_size: trim_size.clone(),
+            margins: Margins::default(),
+            binding_gutter: Unit::zero(),
+            bleed: Unit::zero(),
+            non_printable: Margins::default(),
+            crop_marks: false,
+            debug_overlay: false,
+        }
+    }
+
+    pub fn with_margins(mut self, margins: Margins) -> Self {
+        self.margins = margins;
+        self
+    }
+
+    // Extra margin on the bound edge of each page.
+    pub fn with_binding_gutter(mut self, gutter: Unit) -> Self {
+        self.binding_gutter = gutter;
+        self
+    }
+
+    // How far artwork may extend past the trim so that it will be cut off cleanly.
+    pub fn with_bleed(mut self, bleed: Unit) -> Self {
+        self.bleed = bleed;
+        self
+    }
+
+    // The area around the edge of the trim that the printer can't print on.
+    // The content rect never extends into this area, whatever the margins.
+    pub fn with_non_printable(mut self, non_printable: Margins) -> Self {
+        self.non_printable = non_printable;
+        self
+    }
+
+    pub fn with_crop_marks(mut self, crop_marks: bool) -> Self {
+        self.crop_marks = crop_marks;
+        self
+    }
+
+    // Draws the trim, bleed, non-printable and content areas over each page.
+    pub fn with_debug_overlay(mut self, debug_overlay: bool) -> Self {
+        self.debug_overlay = debug_overlay;
+        self
+    }
+
+    // Distance from the trim to the start of the crop marks.
+    fn crop_mark_offset(&self) -> Unit {
+        self.bleed.max(Self::CROP_MARK_OFFSET.inches())
+    }
+
+    // Distance from the edge of the media to the trim.
+    fn outset(&self) -> Unit {
+        if self.crop_marks {
+            // Room for the marks, which start past the bleed.
+            self.crop_mark_offset() + Self::CROP_MARK_LENGTH.inches()
+        } else {
+            self.bleed
+        }
+    }
+
+    pub fn media_rect(&self) -> WRect {
+        let outset = self.outset();
+        let width = self.trim_size.width() + outset * 2;
+        let height = self.trim_size.height() + outset * 2;
+        WRect::with_dimensions(width, height).move_to(Unit::zero(), height)
+    }
+
+    pub fn trim_rect(&self) -> WRect {
+        let outset = self.outset();
+        self.media_rect().inset_q1(outset, outset)
+    }
+
+    pub fn bleed_rect(&self) -> WRect {
+        self.trim_rect().inset_q1(-self.bleed, -self.bleed)
+    }
+
+    // The boxes that tell a print workflow where the finished page is in the media. None when the
+    // media is the trim, since that's what the PDF defaults to.
+    pub(crate) fn page_boxes(&self) -> Option<PageBoxes> {
+        if self.outset() == Unit::zero() {
+            None
+        } else {
+            Some(PageBoxes {
+                trim: self.trim_rect(),
+                bleed: self.bleed_rect(),
+            })
+        }
+    }
+
+    // The area of the page that the printer can reach.
+    pub fn printable_rect(&self) -> WRect {
+        self.non_printable.inset(&self.trim_rect())
+    }
+
+    // The area inside the margins, where the content of page number `page` should go.
+    pub fn content_rect(&self, page: usize) -> WRect {
+        let mut margins = self.margins;
+        if page % 2 == 1 {
+            margins.left = margins.left + self.binding_gutter;
+        } else {
+            margins.right = margins.right + self.binding_gutter;
+        }
+        margins.max(&self.non_printable).inset(&self.trim_rect())
+    }
+
+    // The content rect of page number `page`, with its origin at the top-left.
+    pub fn content_frame(&self, page: usize) -> Frame {
+        Frame::new(&self.content_rect(page))
+    }
+
+    // Instructions for the crop marks and debug overlay of page number `page`, if requested.
+    pub fn decorations(&self, page: usize) -> Instructions {
+        let mut instructions = Instructions::default();
+        if self.crop_marks {
+            self.render_crop_marks(&mut instructions);
+        }
+        if self.debug_overlay {
+            self.render_debug_overlay(page, &mut instructions);
+        }
+        instructions
+    }
+
+    fn render_crop_marks(&self, instructions: &mut Instructions) {
+        instructions.push_state();
+        instructions.set_stroke_color(Colors::black());
+        instructions.set_stroke_width(0.25);
+        instructions.clear_dash();
+
+        // Marks are outside of the bleed, so that they are never printed over.
+        let trim = self.trim_rect();
+        let offset = self.crop_mark_offset();
+        let length = Self::CROP_MARK_LENGTH.inches();
+        for x in [trim.left(), trim.right()] {
+            for y in [trim.top(), trim.bottom_q1()] {
+                // Direction away from the trim.
+                let dx = if x == trim.left() { -1.0 } else { 1.0 };
+                let dy = if y == trim.top() { 1.0 } else { -1.0 };
+
+                let horiz_start = x + offset * dx;
+                let horiz = WLine::line(horiz_start, y, horiz_start + length * dx, y);
+                instructions.push_shape(horiz.stroke());
+
+                let vert_start = y + offset * dy;
+                let vert = WLine::line(x, vert_start, x, vert_start + length * dy);
+                instructions.push_shape(vert.stroke());
+            }
+        }
+
+        instructions.pop_state();
+    }
+
+    fn render_debug_overlay(&self, page: usize, instructions: &mut Instructions) {
+        instructions.push_state();
+        instructions.set_stroke_width(0.5);
+        instructions.clear_dash();
+
+        instructions.set_stroke_color(Colors::red());
+        instructions.push_shape(self.bleed_rect().stroke());
+
+        instructions.set_stroke_color(Colors::black());
+        instructions.push_shape(self.trim_rect().stroke());
+
+        instructions.set_dash(2, 2);
+        instructions.set_stroke_color(Colors::rgb(1.0, 0.5, 0.0));
+        instructions.push_shape(self.printable_rect().stroke());
+
+        instructions.set_stroke_color(Colors::blue());
+        instructions.push_shape(self.content_rect(page).stroke());
+
+        instructions.pop_state();
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PageBoxes {
+    trim: WRect,
+    bleed: WRect,
+}
+
+// Sets the TrimBox and BleedBox of every page. Without them, the trim is the whole media,
+// crop marks and all.
+pub(crate) fn embed_page_boxes(pdf: &mut lopdf::Document, boxes: &PageBoxes) -> Result<()> {
+    for page_id in pdf.get_pages().into_values() {
+        let page = pdf.get_object_mut(page_id)?.as_dict_mut()?;
+        page.set("TrimBox", pdf_rect(&boxes.trim));
+        page.set("BleedBox", pdf_rect(&boxes.bleed));
+    }
+    Ok(())
+}
@@ -1,6 +1,10 @@
 use crate::{NumericUnit, Unit, WRect};
 
 pub fn cornell_rule_height() -> Unit {
+    college_rule_height()
+}
+
+pub fn college_rule_height() -> Unit {
     (9.0 / 32.0).inches()
 }
 
@@ -8,6 +12,15 @@ pub fn wide_rule_height() -> Unit {
     (11.0 / 32.0).inches()
 }
 
+pub fn narrow_rule_height() -> Unit {
+    0.25.inches()
+}
+
+// Gregg-ruled steno pads.
+pub fn gregg_rule_height() -> Unit {
+    (3.0 / 8.0).inches()
+}
+
 pub fn letter() -> WRect {
     quadrant1(8.5.inches(), 11.0.inches())
 }
@@ -37,6 +50,11 @@ pub fn remarkable2() -> WRect {
     quadrant1(REMARKABLE_WIDTH_MM.mm(), REMARKABLE_HEIGHT_MM.mm())
 }
 
+// The same paper, turned sideways.
+pub fn landscape(paper: &WRect) -> WRect {
+    quadrant1(paper.height(), paper.width())
+}
+
 const fn quadrant1(width: Unit, height: Unit) -> WRect {
     WRect::with_dimensions(width, height).move_to(Unit::zero(), height)
 }
@@ -36,6 +36,14 @@ impl TextContext {
         }
     }
 
+    pub fn font(&self) -> FontProxy {
+        self.proxy
+    }
+
+    pub fn text_height(&self) -> Unit {
+        self.text_height
+    }
+
     pub fn render(&self, txt: impl AsRef<str>, x: Unit, y: Unit, instructions: &mut Instructions) {
         instructions.push_text(txt.as_ref(), self.text_height.to_mm(), x, y, self.proxy);
     }
@@ -0,0 +1,149 @@
+use crate::{Unit, WRect};
+use printpdf::{CurTransMat, Pt};
+
+/// An affine transform of page coordinates.
+///
+/// Transforms are built from the simple constructors below and combined with `then()`, so that
+/// `Transform::rotate(90.0).then(&Transform::translate(x, y))` rotates and then moves.
+/// Angles are in degrees, counterclockwise.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Transform {
+    // x' = a * x + c * y + e
+    // y' = b * x + d * y + f
+    a: f64,
+    b: f64,
+    c: f64,
+    d: f64,
+    e: Unit,
+    f: Unit,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::identity()
+    }
+}
+
+impl Transform {
+    pub const fn identity() -> Transform {
+        Transform {
+            a: 1.0,
+            b: 0.0,
+            c: 0.0,
+            d: 1.0,
+            e: Unit::zero(),
+            f: Unit::zero(),
+        }
+    }
+
+    pub fn translate(x: Unit, y: Unit) -> Transform {
+        Transform {
+            e: x,
+            f: y,
+            ..Transform::identity()
+        }
+    }
+
+    // Rotates about the origin.
+    pub fn rotate(degrees: f64) -> Transform {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Transform {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            ..Transform::identity()
+        }
+    }
+
+    pub fn rotate_about(degrees: f64, x: Unit, y: Unit) -> Transform {
+        Transform::translate(-x, -y)
+            .then(&Transform::rotate(degrees))
+            .then(&Transform::translate(x, y))
+    }
+
+    // Scales about the origin. Negative factors mirror.
+    pub fn scale(sx: f64, sy: f64) -> Transform {
+        Transform {
+            a: sx,
+            d: sy,
+            ..Transform::identity()
+        }
+    }
+
+    pub fn scale_about(sx: f64, sy: f64, x: Unit, y: Unit) -> Transform {
+        Transform::translate(-x, -y)
+            .then(&Transform::scale(sx, sy))
+            .then(&Transform::translate(x, y))
+    }
+
+    // Slants the x axis by `x_degrees` and the y axis by `y_degrees`.
+    pub fn skew(x_degrees: f64, y_degrees: f64) -> Transform {
+        Transform {
+            b: y_degrees.to_radians().tan(),
+            c: x_degrees.to_radians().tan(),
+            ..Transform::identity()
+        }
+    }
+
+    // Reflects left-to-right across the vertical line at `x`.
+    pub fn mirror_horizontal(x: Unit) -> Transform {
+        Transform::scale_about(-1.0, 1.0, x, Unit::zero())
+    }
+
+    // Reflects top-to-bottom across the horizontal line at `y`.
+    pub fn mirror_vertical(y: Unit) -> Transform {
+        Transform::scale_about(1.0, -1.0, Unit::zero(), y)
+    }
+
+    /// Maps `source` onto `target`, after rotating it by `degrees` about its center.
+    ///
+    /// The rotated source is scaled uniformly to be as large as possible while still fitting in
+    /// the target, and is centered there. So, to print a landscape layout on a portrait page,
+    /// lay it out in a landscape rect and use `Transform::fit(&landscape, 90.0, &page)`.
+    pub fn fit(source: &WRect, degrees: f64, target: &WRect) -> Transform {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let rotated_width = source.width() * cos.abs() + source.height() * sin.abs();
+        let rotated_height = source.width() * sin.abs() + source.height() * cos.abs();
+        let scale = (target.width() / rotated_width).min(target.height() / rotated_height);
+
+        let (source_x, source_y) = center(source);
+        let (target_x, target_y) = center(target);
+        Transform::translate(-source_x, -source_y)
+            .then(&Transform::rotate(degrees))
+            .then(&Transform::scale(scale, scale))
+            .then(&Transform::translate(target_x, target_y))
+    }
+
+    // A transform that applies `self`, and then `next`.
+    pub fn then(&self, next: &Transform) -> Transform {
+        Transform {
+            a: self.a * next.a + self.b * next.c,
+            b: self.a * next.b + self.b * next.d,
+            c: self.c * next.a + self.d * next.c,
+            d: self.c * next.b + self.d * next.d,
+            e: self.e * next.a + self.f * next.c + next.e,
+            f: self.e * next.b + self.f * next.d + next.f,
+        }
+    }
+
+    pub fn apply(&self, x: Unit, y: Unit) -> (Unit, Unit) {
+        (
+            x * self.a + y * self.c + self.e,
+            x * self.b + y * self.d + self.f,
+        )
+    }
+
+    pub(crate) fn to_ctm(self) -> CurTransMat {
+        let e: Pt = self.e.into();
+        let f: Pt = self.f.into();
+        CurTransMat::Raw([self.a, self.b, self.c, self.d, e.0, f.0])
+    }
+}
+
+fn center(rect: &WRect) -> (Unit, Unit) {
+    (
+        rect.left() + rect.width() / 2,
+        rect.bottom_q1() + rect.height() / 2,
+    )
+}
@@ -0,0 +1,109 @@
+use crate::cells::CellRenderer;
+use crate::{
+    Attributes, Colors, HasRenderAttrs, Instructions, LinkTarget, TextContext, Transform, Unit,
+    WRect,
+};
+
+/// A column of labelled tabs that link to other pages, like the dividers in a binder.
+///
+/// The tabs share the height of the rect they are rendered in, and the labels read from top to
+/// bottom, so they are meant to run down the right edge of a page.
+#[derive(Debug, Clone)]
+pub struct Tabs {
+    tabs: Vec<(String, LinkTarget)>,
+    selected: Option<usize>,
+    text_context: TextContext,
+    attributes: Attributes,
+}
+
+impl Default for Tabs {
+    fn default() -> Self {
+        Tabs {
+            tabs: vec![],
+            selected: None,
+            text_context: TextContext::helvetica(),
+            attributes: Attributes::default()
+                .with_stroke_width(0.5)
+                .with_stroke_color(&Colors::gray(0.5)),
+        }
+    }
+}
+
+impl Tabs {
+    pub fn with_tab(mut self, label: impl Into<String>, target: LinkTarget) -> Self {
+        self.tabs.push((label.into(), target));
+        self
+    }
+
+    // Shades the tab for the page that the tabs are on.
+    pub fn with_selected(mut self, selected: Option<usize>) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    // The font for the labels. They are sized to fit the tabs.
+    pub fn with_text_context(mut self, text_context: &TextContext) -> Self {
+        self.text_context = text_context.clone();
+        self
+    }
+
+    pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+}
+
+impl CellRenderer for Tabs {
+    fn render(&self, rect: &WRect, instructions: &mut Instructions) {
+        if self.tabs.is_empty() {
+            return;
+        }
+
+        let font = self.text_context.font();
+        let tab_height = rect.height() / self.tabs.len() as f64;
+        let tab_rect = WRect::with_dimensions(rect.width(), tab_height);
+
+        // One font size for every tab, small enough for the longest label.
+        let cap_height = rect.width().pct(40.0);
+        let font_size = self
+            .tabs
+            .iter()
+            .map(|(label, _)| {
+                let font_size = cap_height / font.cap_height(1.0);
+                let available = tab_height.pct(80.0);
+                let width = font.text_width(label, font_size);
+                if width > available {
+                    font_size * (available / width)
+                } else {
+                    font_size
+                }
+            })
+            .fold(f64::MAX, f64::min);
+
+        for (index, (label, target)) in self.tabs.iter().enumerate() {
+            let this_rect = tab_rect.move_to(rect.left(), rect.top() - tab_height * index as f64);
+            self.attributes.render(instructions, |instructions| {
+                if self.selected == Some(index) {
+                    instructions.set_fill_color(Colors::gray(0.85));
+                    instructions.push_shape(this_rect.clone().fill().stroke());
+                } else {
+                    instructions.push_shape(this_rect.clone().stroke());
+                }
+            });
+
+            // Rotated clockwise, the text runs down the page and the letters grow to the right.
+            let text_width = font.text_width(label, font_size);
+            let x = this_rect.left() + (rect.width() - font.cap_height(font_size)) / 2;
+            let y = this_rect.top() - (tab_height - text_width) / 2;
+            let transform = Transform::rotate(-90.0).then(&Transform::translate(x, y));
+            instructions.push_state();
+            instructions.set_fill_color(Colors::black());
+            instructions.with_transform(&transform, |instructions| {
+                instructions.push_text(label, font_size, Unit::zero(), Unit::zero(), font);
+            });
+            instructions.pop_state();
+
+            instructions.link(&this_rect, target.clone());
+        }
+    }
+}
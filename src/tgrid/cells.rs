@@ -0,0 +1,383 @@
+//! Reusable renderers for the contents of grid cells.
+//!
+//! Each renderer draws into the `WRect` of a single cell, so they can be called directly from
+//! `GridDescription::render_cell_contents()`. Shapes are drawn with the renderer's
+//! `Attributes`, so colors and stroke widths that are left unset are inherited from the current
+//! graphics state.
+
+use crate::{
+    Attributes, Circle, FormField, HasRenderAttrs, Instructions, NumericUnit, TextContext, Unit,
+    WLine, WRect,
+};
+use printpdf::Color;
+
+pub trait CellRenderer {
+    fn render(&self, cell_rect: &WRect, instructions: &mut Instructions);
+}
+
+/// Horizontal placement of a cell's contents.
+///
+/// Left and Right alignments inset the contents from the side of the cell by the same amount
+/// that they are inset from the top and bottom.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum CellAlignment {
+    Left,
+    #[default]
+    Center,
+    Right,
+}
+
+impl CellAlignment {
+    // Returns the left edge of something `width` wide, inset `side_inset` if aligned to a side.
+    fn left(&self, cell_rect: &WRect, width: Unit, side_inset: Unit) -> Unit {
+        match self {
+            CellAlignment::Left => cell_rect.left() + side_inset,
+            CellAlignment::Center => cell_rect.left() + (cell_rect.width() - width) / 2,
+            CellAlignment::Right => cell_rect.right() - side_inset - width,
+        }
+    }
+}
+
+/// A square box, vertically centered in the cell.
+#[derive(Debug, Clone)]
+pub struct Checkbox {
+    size: Unit,
+    alignment: CellAlignment,
+    corner_radius: Option<Unit>,
+    attributes: Attributes,
+    field_name: Option<String>,
+}
+
+impl Checkbox {
+    pub fn new(size: Unit) -> Checkbox {
+        Checkbox {
+            size,
+            alignment: CellAlignment::default(),
+            corner_radius: None,
+            attributes: Attributes::default(),
+            field_name: None,
+        }
+    }
+
+    // Adds a checkbox form field, with this name, over the box.
+    pub fn with_field(mut self, name: impl Into<String>) -> Self {
+        self.field_name = Some(name.into());
+        self
+    }
+
+    pub fn with_alignment(mut self, alignment: CellAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    pub fn with_corner_radius(mut self, radius: Unit) -> Self {
+        self.corner_radius = Some(radius);
+        self
+    }
+}
+
+impl CellRenderer for Checkbox {
+    fn render(&self, cell_rect: &WRect, instructions: &mut Instructions) {
+        let y_offset = (cell_rect.height() - self.size) / 2;
+        let x = self.alignment.left(cell_rect, self.size, y_offset);
+
+        let mut checkbox_rect =
+            WRect::with_dimensions(self.size, self.size).move_to(x, cell_rect.top() - y_offset);
+        if let Some(radius) = self.corner_radius {
+            checkbox_rect.set_corner_radius(radius);
+        }
+
+        if let Some(name) = &self.field_name {
+            instructions.field(&checkbox_rect, FormField::checkbox(name.as_str()));
+        }
+        self.attributes.render(instructions, |instructions| {
+            instructions.push_shape(checkbox_rect.stroke());
+        });
+    }
+}
+
+/// A circle, vertically centered in the cell, for bulleted lists.
+#[derive(Debug, Clone)]
+pub struct Bullet {
+    radius: Unit,
+    alignment: CellAlignment,
+    filled: bool,
+    attributes: Attributes,
+}
+
+impl Bullet {
+    pub fn new(radius: Unit) -> Bullet {
+        Bullet {
+            radius,
+            alignment: CellAlignment::default(),
+            filled: false,
+            attributes: Attributes::default(),
+        }
+    }
+
+    pub fn with_alignment(mut self, alignment: CellAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    pub fn filled(mut self, filled: bool) -> Self {
+        self.filled = filled;
+        self
+    }
+}
+
+impl CellRenderer for Bullet {
+    fn render(&self, cell_rect: &WRect, instructions: &mut Instructions) {
+        let diameter = self.radius * 2;
+        let y_offset = (cell_rect.height() - diameter) / 2;
+        let x = self.alignment.left(cell_rect, diameter, y_offset) + self.radius;
+        let y = cell_rect.bottom_q1() + cell_rect.height() / 2;
+
+        let circle = Circle::at_zero(self.radius).move_to(x, y);
+        self.attributes.render(instructions, |instructions| {
+            if self.filled {
+                instructions.push_shape(circle.fill());
+            } else {
+                instructions.push_shape(circle.stroke());
+            }
+        });
+    }
+}
+
+/// A circle divided into equal segments by spokes, to be shaded in as progress is made.
+#[derive(Debug, Clone)]
+pub struct ProgressRing {
+    radius: Unit,
+    segments: usize,
+    alignment: CellAlignment,
+    attributes: Attributes,
+}
+
+impl ProgressRing {
+    pub fn new(radius: Unit, segments: usize) -> ProgressRing {
+        ProgressRing {
+            radius,
+            segments,
+            alignment: CellAlignment::default(),
+            attributes: Attributes::default(),
+        }
+    }
+
+    pub fn with_alignment(mut self, alignment: CellAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+}
+
+impl CellRenderer for ProgressRing {
+    fn render(&self, cell_rect: &WRect, instructions: &mut Instructions) {
+        let diameter = self.radius * 2;
+        let y_offset = (cell_rect.height() - diameter) / 2;
+        let x = self.alignment.left(cell_rect, diameter, y_offset) + self.radius;
+        let y = cell_rect.bottom_q1() + cell_rect.height() / 2;
+
+        self.attributes.render(instructions, |instructions| {
+            instructions.push_shape(Circle::at_zero(self.radius).move_to(x, y).stroke());
+
+            // The first spoke points straight up, and the rest follow clockwise.
+            if self.segments > 1 {
+                for segment in 0..self.segments {
+                    let angle = (90.0 - 360.0 * segment as f64 / self.segments as f64).to_radians();
+                    let spoke = WLine::line(
+                        x,
+                        y,
+                        x + self.radius * angle.cos(),
+                        y + self.radius * angle.sin(),
+                    );
+                    instructions.push_shape(spoke.stroke());
+                }
+            }
+        });
+    }
+}
+
+/// A row of small boxes for keeping a tally, centered in the cell.
+#[derive(Debug, Clone)]
+pub struct TallyBoxes {
+    count: usize,
+    size: Unit,
+    gap: Unit,
+    attributes: Attributes,
+}
+
+impl TallyBoxes {
+    pub fn new(count: usize, size: Unit) -> TallyBoxes {
+        TallyBoxes {
+            count,
+            size,
+            gap: size / 2,
+            attributes: Attributes::default(),
+        }
+    }
+
+    pub fn with_gap(mut self, gap: Unit) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+}
+
+impl CellRenderer for TallyBoxes {
+    fn render(&self, cell_rect: &WRect, instructions: &mut Instructions) {
+        if self.count == 0 {
+            return;
+        }
+
+        let total_width = self.size * self.count as f64 + self.gap * (self.count - 1) as f64;
+        let left = cell_rect.left() + (cell_rect.width() - total_width) / 2;
+        let top = cell_rect.top() - (cell_rect.height() - self.size) / 2;
+
+        let base_rect = WRect::with_dimensions(self.size, self.size);
+        self.attributes.render(instructions, |instructions| {
+            for i in 0..self.count {
+                let x = left + (self.size + self.gap) * i as f64;
+                instructions.push_shape(base_rect.move_to(x, top).stroke());
+            }
+        });
+    }
+}
+
+/// A line of text, vertically centered in the cell.
+#[derive(Debug, Clone)]
+pub struct CellText {
+    text: String,
+    text_context: TextContext,
+    alignment: CellAlignment,
+    inset: Unit,
+    color: Option<Color>,
+}
+
+impl CellText {
+    pub fn new(text: impl Into<String>, text_context: &TextContext) -> CellText {
+        CellText {
+            text: text.into(),
+            text_context: text_context.clone(),
+            alignment: CellAlignment::Left,
+            inset: 1.5.mm(),
+            color: None,
+        }
+    }
+
+    pub fn with_alignment(mut self, alignment: CellAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    // Distance from the side of the cell for Left and Right aligned text.
+    pub fn with_inset(mut self, inset: Unit) -> Self {
+        self.inset = inset;
+        self
+    }
+
+    pub fn with_color(mut self, color: &Color) -> Self {
+        self.color = Some(color.clone());
+        self
+    }
+}
+
+impl CellRenderer for CellText {
+    fn render(&self, cell_rect: &WRect, instructions: &mut Instructions) {
+        let font = self.text_context.font();
+        let font_size = self.text_context.text_height().to_mm();
+        let width = font.text_width(&self.text, font_size);
+        let x = self.alignment.left(cell_rect, width, self.inset);
+        let y = cell_rect.bottom_q1() + (cell_rect.height() - font.cap_height(font_size)) / 2;
+
+        instructions.push_state();
+        if let Some(color) = &self.color {
+            instructions.set_fill_color(color.clone());
+        }
+        self.text_context.render(&self.text, x, y, instructions);
+        instructions.pop_state();
+    }
+}
+
+/// A day-of-the-month number tucked into the top corner of the cell, as on a calendar.
+/// The number is sized relative to the cell.
+#[derive(Debug, Clone)]
+pub struct DayNumber {
+    day: u32,
+    text_context: TextContext,
+    alignment: CellAlignment,
+    // Height of the digits as a percentage of the cell height.
+    height_pct: f64,
+    color: Option<Color>,
+}
+
+impl DayNumber {
+    pub fn new(day: u32) -> DayNumber {
+        DayNumber {
+            day,
+            text_context: TextContext::helvetica(),
+            alignment: CellAlignment::Left,
+            height_pct: 20.0,
+            color: None,
+        }
+    }
+
+    pub fn with_text_context(mut self, text_context: &TextContext) -> Self {
+        self.text_context = text_context.clone();
+        self
+    }
+
+    pub fn with_alignment(mut self, alignment: CellAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn with_height_pct(mut self, height_pct: f64) -> Self {
+        self.height_pct = height_pct;
+        self
+    }
+
+    pub fn with_color(mut self, color: &Color) -> Self {
+        self.color = Some(color.clone());
+        self
+    }
+}
+
+impl CellRenderer for DayNumber {
+    fn render(&self, cell_rect: &WRect, instructions: &mut Instructions) {
+        let text = self.day.to_string();
+        let font = self.text_context.font();
+
+        let digit_height = cell_rect.height().pct(self.height_pct);
+        let font_size = digit_height / font.cap_height(1.0);
+        let inset = digit_height / 2;
+
+        let width = font.text_width(&text, font_size);
+        let x = self.alignment.left(cell_rect, width, inset);
+        let y = cell_rect.top() - inset - digit_height;
+
+        instructions.push_state();
+        if let Some(color) = &self.color {
+            instructions.set_fill_color(color.clone());
+        }
+        instructions.push_text(&text, font_size, x, y, font);
+        instructions.pop_state();
+    }
+}
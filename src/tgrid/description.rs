@@ -3,7 +3,41 @@ use std::borrow::Cow;
 
 use crate::pdfutils::Attributes;
 use crate::pdfutils::FontProxy;
-use crate::{Instructions, NumericUnit, Unit, WRect};
+use crate::{FormField, Instructions, LinkTarget, NumericUnit, Unit, WRect};
+
+/// The direction in which label text runs.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LabelOrientation {
+    // Left-to-right, centered over the column.
+    Horizontal,
+    // Bottom-to-top, rotated 90° counter-clockwise.
+    Vertical,
+    // Rotated counter-clockwise by the given number of degrees, like spreadsheet headers.
+    Angled(f64),
+}
+
+impl LabelOrientation {
+    pub fn degrees(&self) -> f64 {
+        match self {
+            LabelOrientation::Horizontal => 0.0,
+            LabelOrientation::Vertical => 90.0,
+            LabelOrientation::Angled(deg) => *deg,
+        }
+    }
+
+    // Returns the height that a label of the given dimensions occupies with this orientation.
+    pub fn label_extent(&self, text_width: Unit, cap_height: Unit) -> Unit {
+        let radians = self.degrees().to_radians();
+        text_width * radians.sin().abs() + cap_height * radians.cos().abs()
+    }
+
+    // Returns how far across a label of the given dimensions reaches from the middle of its
+    // column with this orientation: to the right if positive, to the left if negative.
+    pub fn label_reach(&self, text_width: Unit, cap_height: Unit) -> Unit {
+        let radians = self.degrees().to_radians();
+        text_width * radians.cos() + cap_height / 2 * radians.sin()
+    }
+}
 
 pub trait GridDescription {
     // Returns the page bounds of the table.
@@ -39,6 +73,26 @@ pub trait GridDescription {
         None
     }
 
+    // Number of rows to render on each page when the grid is split with TGrid::paginate().
+    // If row_height() is None, rows are sized so that this many fill the bounds.
+    // Returning None fits as many rows as possible into the bounds.
+    //
+    // Defaults to None.
+    fn rows_per_page(&self) -> Option<usize> {
+        None
+    }
+
+    // Whether pages after the first repeat the column(row) labels when paginating.
+    // Pages without row labels still reserve their width, so columns line up across pages.
+    //
+    // Defaults to true.
+    fn repeat_col_labels(&self) -> bool {
+        true
+    }
+    fn repeat_row_labels(&self) -> bool {
+        true
+    }
+
     // Width(height) of the row(column) label. If None, labels are not rendered.
     // Defaults to None.
     fn row_label_width(&self) -> Option<Unit> {
@@ -48,15 +102,41 @@ pub trait GridDescription {
         None
     }
 
+    // Width(height) of the row(column) labels on the right(bottom) of the grid.
+    // If None, those labels are not rendered.
+    // Defaults to None.
+    fn right_row_label_width(&self) -> Option<Unit> {
+        None
+    }
+    fn bottom_col_label_height(&self) -> Option<Unit> {
+        None
+    }
+
     // Text for the row(col) label.
     // index will always be < num_rows(num_cols)
-    fn row_label(&self, _index: usize) -> Cow<str> {
+    fn row_label(&self, _index: usize) -> Cow<'_, str> {
         "".into()
     }
-    fn col_label(&self, _index: usize) -> Cow<str> {
+    fn col_label(&self, _index: usize) -> Cow<'_, str> {
         "".into()
     }
 
+    // Text for the row(col) label on the right(bottom) of the grid.
+    // Defaults to repeating row_label(col_label).
+    fn right_row_label(&self, index: usize) -> Cow<'_, str> {
+        self.row_label(index)
+    }
+    fn bottom_col_label(&self, index: usize) -> Cow<'_, str> {
+        self.col_label(index)
+    }
+
+    // Direction in which the column labels are drawn.
+    // Labels on the bottom of the grid are mirrored so that they hang down from the grid.
+    // Defaults to Vertical.
+    fn col_label_orientation(&self) -> LabelOrientation {
+        LabelOrientation::Vertical
+    }
+
     // Returns optional Attributes which will be used to render the lines.
     // Returning None will not draw a line.
     // Returning Attributes::default() will render:
@@ -76,10 +156,40 @@ pub trait GridDescription {
         Some(Default::default())
     }
 
+    // The line at `index` where a paginated grid is split between pages. It is drawn at the bottom
+    // of one page and again at the top of the next, instead of the horiz_line_style() line.
+    //
+    // Defaults to the style of the final line, so every page is closed off like the last one.
+    fn page_break_line_style(&self, _index: usize, num_rows: usize) -> Option<Attributes> {
+        self.horiz_line_style(num_rows, num_rows)
+    }
+
     fn column_background(&self, _index: usize) -> Option<Color> {
         None
     }
 
+    // Fills the cell before its contents are rendered, on top of any column background.
+    //
+    // Defaults to None.
+    fn cell_background(&self, _row: usize, _col: usize) -> Option<Color> {
+        None
+    }
+
+    // Makes the whole cell a link, for PDFs that are used on a screen.
+    //
+    // Defaults to None.
+    fn cell_link(&self, _row: usize, _col: usize) -> Option<LinkTarget> {
+        None
+    }
+
+    // Covers the cell with a form field that can be filled in on a screen. Checkmarks are drawn
+    // in the middle of the cell, so they line up with a centered `cells::Checkbox`.
+    //
+    // Defaults to None.
+    fn cell_field(&self, _row: usize, _col: usize) -> Option<FormField> {
+        None
+    }
+
     fn render_cell_contents(
         &self,
         _row: usize,
@@ -89,6 +199,19 @@ pub trait GridDescription {
     ) {
     }
 
+    // Returns a description of a grid to render inside the cell, after render_cell_contents().
+    // The child grid is laid out in `cell_rect`; its own bounds() are ignored.
+    //
+    // Defaults to None.
+    fn cell_grid(
+        &self,
+        _row: usize,
+        _col: usize,
+        _cell_rect: &WRect,
+    ) -> Option<Box<dyn GridDescription + '_>> {
+        None
+    }
+
     // Font to use for labels.
     // TODO: allow returning a font size
     // TODO: allow returning a different font in different parts of the grid.
@@ -1,37 +1,48 @@
 use crate::shapes::HasRenderAttrs;
 use crate::tgrid::renderparams::RenderParams;
-use crate::{Colors, Instructions, NumericUnit, Unit, WLine, WRect};
-use description::GridDescription;
+use crate::{Colors, Instructions, NumericUnit, Transform, Unit, WLine, WRect};
+use description::{GridDescription, LabelOrientation};
+use std::borrow::Cow;
 
+pub mod cells;
 pub mod description;
 mod renderparams;
 
 pub struct TGrid<D>
 where
-    D: GridDescription,
+    D: GridDescription + ?Sized,
 {
     params: RenderParams<D>,
 }
 
 impl<D> TGrid<D>
 where
-    D: GridDescription,
+    D: GridDescription + ?Sized,
 {
-    pub fn with_description(description: D) -> TGrid<D> {
+    pub fn with_description(description: D) -> TGrid<D>
+    where
+        D: Sized,
+    {
+        let bounds = description.bounds();
         TGrid {
-            params: description.into(),
+            params: RenderParams::new(Box::new(description), bounds),
+        }
+    }
+
+    // Creates a grid laid out in `bounds`, ignoring the bounds() of the description.
+    // Used to render a grid inside the cell of another grid.
+    pub fn nested_in(description: Box<D>, bounds: &WRect) -> TGrid<D> {
+        TGrid {
+            params: RenderParams::new(description, bounds.clone()),
         }
     }
 
     fn render_horizontal_lines(&self, instructions: &mut Instructions) {
         let left = self.params.grid_bounds.left();
-        let right = left
-            + self.params.row_label_width
-            + self.params.col_width * self.params.num_cols as f64;
+        let right = self.col_x(self.params.num_cols) + self.params.right_row_label_width;
         let top = self.params.grid_bounds.top() - self.params.col_label_height;
-        let num_rows = self.params.num_rows;
-        for row in 0..=num_rows {
-            if let Some(attrs) = self.params.horiz_line_style(row, num_rows) {
+        for row in 0..=self.params.num_rows {
+            if let Some(attrs) = self.params.horiz_line_style(row) {
                 attrs.render(instructions, |instructions| {
                     let y = top - self.params.row_height * row as f64;
                     instructions.push_shape(WLine::line(left, y, right, y).stroke());
@@ -42,9 +53,7 @@ where
 
     fn render_vertical_lines(&self, instructions: &mut Instructions) {
         let top = self.params.grid_bounds.top();
-        let bottom = top
-            - self.params.col_label_height
-            - self.params.row_height * self.params.num_rows as f64;
+        let bottom = self.row_y(self.params.num_rows) - self.params.bottom_col_label_height;
         let left = self.params.grid_bounds.left() + self.params.row_label_width;
         let num_cols = self.params.num_cols;
         for col in 0..=num_cols {
@@ -70,18 +79,29 @@ where
     }
 
     fn render_row_labels(&self, instructions: &mut Instructions) {
-        if !self.params.has_row_labels {
-            return;
+        if self.params.has_row_labels {
+            let x = self.params.grid_bounds.left() + 2.0.mm();
+            self.render_row_labels_at(x, |row| self.params.row_label(row), instructions);
+        }
+        if self.params.has_right_row_labels {
+            let x = self.col_x(self.params.num_cols) + 2.0.mm();
+            self.render_row_labels_at(x, |row| self.params.right_row_label(row), instructions);
         }
+    }
 
+    fn render_row_labels_at<'a>(
+        &'a self,
+        x: Unit,
+        label: impl Fn(usize) -> Cow<'a, str>,
+        instructions: &mut Instructions,
+    ) {
         let row_height = self.params.row_height;
 
-        let x = self.params.grid_bounds.left() + 2.0.mm();
         let text_height = row_height * 1.9;
         for row in 0..self.params.num_rows {
             let y = self.row_y(row + 1) + 1.5.mm();
             instructions.push_text(
-                self.params.row_label(row).as_ref(),
+                label(row).as_ref(),
                 text_height.to_mm(),
                 x,
                 y,
@@ -91,38 +111,126 @@ where
     }
 
     fn render_col_labels(&self, instructions: &mut Instructions) {
-        if !self.params.has_col_labels {
-            return;
+        if self.params.has_col_labels {
+            let top = self.params.grid_bounds.top();
+            let bottom = top - self.params.col_label_height;
+            self.render_col_labels_in(
+                top,
+                bottom,
+                false,
+                |col| self.params.col_label(col),
+                instructions,
+            );
         }
+        if self.params.has_bottom_col_labels {
+            let top = self.row_y(self.params.num_rows);
+            let bottom = top - self.params.bottom_col_label_height;
+            self.render_col_labels_in(
+                top,
+                bottom,
+                true,
+                |col| self.params.bottom_col_label(col),
+                instructions,
+            );
+        }
+    }
 
-        let row_height = self.params.row_height;
+    // Renders a row of column labels in the band between `top` and `bottom`.
+    // Labels above the grid grow upward from the bottom of the band. Labels below the grid
+    // (`hanging`) are mirrored, so they grow downward from the top of the band.
+    fn render_col_labels_in<'a>(
+        &'a self,
+        top: Unit,
+        bottom: Unit,
+        hanging: bool,
+        label: impl Fn(usize) -> Cow<'a, str>,
+        instructions: &mut Instructions,
+    ) {
+        let orientation = self.params.col_label_orientation;
+        let font = self.params.font;
+        let padding = 1.0.mm();
 
-        // (159, -21) after rotation.
-        let text_height = row_height * 1.9;
-        let y = self.params.grid_bounds.top() - self.params.col_label_height + 1.0.mm();
+        let text_height = self.params.row_height * 1.9;
         for col in 0..self.params.num_cols {
-            let x = self.col_x(col + 1) - 1.0.mm();
+            let label = label(col);
+            let center = self.col_x(col) + self.params.col_width / 2;
 
-            instructions.push_state();
-            instructions.rotate(90.0);
-            instructions.translate(y, -x);
+            let mut font_size = text_height.to_mm();
+            if orientation == LabelOrientation::Horizontal {
+                // Horizontal labels are centered over the column, shrinking them if they won't fit.
+                let available = self.params.col_width - padding * 2;
+                let text_width = font.text_width(&label, font_size);
+                if text_width > available {
+                    font_size *= available / text_width;
+                }
+                let x = center - font.text_width(&label, font_size) / 2;
+                let y = if hanging {
+                    top - padding - font.cap_height(font_size)
+                } else {
+                    bottom + padding
+                };
+                instructions.push_text(&label, font_size, x, y, font);
+                continue;
+            }
 
-            // Text position is (0.0), so that we can rotate the text before translating it.
-            instructions.push_text(
-                self.params.col_label(col).as_ref(),
-                text_height.to_mm(),
-                Unit::zero(),
-                Unit::zero(),
-                self.params.font,
+            // Rotated labels shrink to fit the height of the label area. Their size is
+            // proportional to the font size, so one scale makes them fit.
+            let available = top - bottom - padding * 2;
+            let extent = orientation.label_extent(
+                font.text_width(&label, font_size),
+                font.cap_height(font_size),
             );
-            instructions.pop_state();
+            if extent > available {
+                font_size *= available / extent;
+            }
+
+            // Angled labels also run across the columns beside them. Keep them inside the grid.
+            let reach = orientation.label_reach(
+                font.text_width(&label, font_size),
+                font.cap_height(font_size),
+            );
+            let bounds = &self.params.grid_bounds;
+            let room = if reach > Unit::zero() {
+                bounds.right() - padding - center
+            } else {
+                bounds.left() + padding - center
+            };
+            if reach / room > 1.0 {
+                font_size *= room / reach;
+            }
+
+            // Rotated labels start at the edge of the label area nearest the grid, with the band
+            // of the capital letters centered on the middle of the column.
+            let cap_height = font.cap_height(font_size);
+            let radians = orientation.degrees().to_radians();
+            let (x, y, degrees) = if hanging {
+                (
+                    center - cap_height / 2 * radians.sin(),
+                    top - padding - cap_height * radians.cos(),
+                    -orientation.degrees(),
+                )
+            } else {
+                (
+                    center + cap_height / 2 * radians.sin(),
+                    bottom + padding,
+                    orientation.degrees(),
+                )
+            };
+
+            // Text position is (0.0), so that we can rotate the text before translating it.
+            let transform = Transform::rotate(degrees).then(&Transform::translate(x, y));
+            instructions.with_transform(&transform, |instructions| {
+                instructions.push_text(&label, font_size, Unit::zero(), Unit::zero(), font);
+            });
         }
     }
 
     fn render_column_backgrounds(&self, instructions: &mut Instructions) {
         let base_col_rect = WRect::with_dimensions(
             self.params.col_width,
-            self.params.col_label_height + self.params.row_height * self.params.num_rows as f64,
+            self.params.col_label_height
+                + self.params.row_height * self.params.num_rows as f64
+                + self.params.bottom_col_label_height,
         );
 
         for col in 0..self.params.num_cols {
@@ -141,8 +249,27 @@ where
         for row in 0..self.params.num_rows {
             for col in 0..self.params.num_cols {
                 let this_rect = cell_rect.move_to(self.col_x(col), self.row_y(row));
+                if let Some(color) = self.params.cell_background(row, col) {
+                    instructions.push_state();
+                    instructions.set_fill_color(color);
+                    instructions.push_shape(this_rect.clone().fill());
+                    instructions.pop_state();
+                }
                 self.params
                     .render_cell_contents(row, col, &this_rect, instructions);
+                if let Some(target) = self.params.cell_link(row, col) {
+                    instructions.link(&this_rect, target);
+                }
+                if let Some(field) = self.params.cell_field(row, col) {
+                    instructions.field(&this_rect, field);
+                }
+
+                if let Some(child) = self.params.cell_grid(row, col, &this_rect) {
+                    // Keep the child's line and text settings from leaking into this grid.
+                    instructions.push_state();
+                    TGrid::nested_in(child, &this_rect).append_to_instructions(instructions);
+                    instructions.pop_state();
+                }
             }
         }
     }
@@ -171,4 +298,37 @@ where
         self.append_to_instructions(&mut instructions);
         instructions
     }
+
+    // Splits the grid across as many pages as it takes to render every row, returning the
+    // instructions for each page. Every page uses the same bounds.
+    pub fn paginate(mut self) -> Vec<Instructions> {
+        let mut pages = vec![];
+        let total_rows = self.params.total_rows;
+        loop {
+            let first_row = self.params.first_row;
+            self.params.num_rows = self
+                .params
+                .rows_that_fit()
+                .max(1)
+                .min(total_rows - first_row);
+            pages.push(self.generate_instructions());
+
+            self.params.first_row += self.params.num_rows;
+            if self.params.first_row >= total_rows {
+                break;
+            }
+
+            // Continuation pages.
+            if !self.params.repeat_col_labels {
+                self.params.has_col_labels = false;
+                self.params.col_label_height = Unit::zero();
+            }
+            if !self.params.repeat_row_labels {
+                // Keep the label width so that the columns line up from page to page.
+                self.params.has_row_labels = false;
+                self.params.has_right_row_labels = false;
+            }
+        }
+        pages
+    }
 }
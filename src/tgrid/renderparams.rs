@@ -1,15 +1,16 @@
 use crate::pdfutils::Attributes;
 use crate::pdfutils::FontProxy;
-use crate::{GridDescription, Instructions, Unit, WRect};
+use crate::tgrid::description::LabelOrientation;
+use crate::{FormField, GridDescription, Instructions, LinkTarget, Unit, WRect};
 use printpdf::Color;
 use std::borrow::Cow;
 
 #[derive(Debug)]
 pub struct RenderParams<D>
 where
-    D: GridDescription,
+    D: GridDescription + ?Sized,
 {
-    description: D,
+    description: Box<D>,
 
     pub grid_bounds: WRect,
 
@@ -18,29 +19,71 @@ where
     pub num_rows: usize,
     pub num_cols: usize,
 
+    // When paginating, only `num_rows` rows starting at `first_row` are rendered.
+    // The description always sees indices into the full set of `total_rows` rows.
+    pub first_row: usize,
+    pub total_rows: usize,
+    pub rows_per_page: Option<usize>,
+    pub repeat_col_labels: bool,
+    pub repeat_row_labels: bool,
+
     pub has_row_labels: bool,
     pub row_label_width: Unit,
 
+    pub has_right_row_labels: bool,
+    pub right_row_label_width: Unit,
+
     pub has_col_labels: bool,
     pub col_label_height: Unit,
+    pub col_label_orientation: LabelOrientation,
+
+    pub has_bottom_col_labels: bool,
+    pub bottom_col_label_height: Unit,
 
     pub font: FontProxy,
 }
 
 impl<D> RenderParams<D>
 where
-    D: GridDescription,
+    D: GridDescription + ?Sized,
 {
-    pub fn row_label(&self, index: usize) -> Cow<str> {
-        self.description.row_label(index)
+    // The number of rows that fit on one page, given the labels currently being rendered.
+    pub fn rows_that_fit(&self) -> usize {
+        self.rows_per_page.unwrap_or_else(|| {
+            let cells_height =
+                self.grid_bounds.height() - self.col_label_height - self.bottom_col_label_height;
+            // Allow for a little rounding error so that exactly fitting rows aren't dropped.
+            ((cells_height / self.row_height) + 1e-9).trunc() as usize
+        })
     }
 
-    pub fn col_label(&self, index: usize) -> Cow<str> {
+    pub fn row_label(&self, index: usize) -> Cow<'_, str> {
+        self.description.row_label(self.first_row + index)
+    }
+
+    pub fn col_label(&self, index: usize) -> Cow<'_, str> {
         self.description.col_label(index)
     }
 
-    pub fn horiz_line_style(&self, index: usize, num_rows: usize) -> Option<Attributes> {
-        self.description.horiz_line_style(index, num_rows)
+    pub fn right_row_label(&self, index: usize) -> Cow<'_, str> {
+        self.description.right_row_label(self.first_row + index)
+    }
+
+    pub fn bottom_col_label(&self, index: usize) -> Cow<'_, str> {
+        self.description.bottom_col_label(index)
+    }
+
+    // `index` is the line on this page. Lines where the grid is split between pages are styled by
+    // `page_break_line_style()` instead.
+    pub fn horiz_line_style(&self, index: usize) -> Option<Attributes> {
+        let row = self.first_row + index;
+        let at_page_break =
+            (index == 0 && self.first_row > 0) || (index == self.num_rows && row < self.total_rows);
+        if at_page_break {
+            self.description.page_break_line_style(row, self.total_rows)
+        } else {
+            self.description.horiz_line_style(row, self.total_rows)
+        }
     }
 
     pub fn vert_line_style(&self, index: usize, num_cols: usize) -> Option<Attributes> {
@@ -51,6 +94,18 @@ where
         self.description.column_background(index)
     }
 
+    pub fn cell_background(&self, row: usize, col: usize) -> Option<Color> {
+        self.description.cell_background(self.first_row + row, col)
+    }
+
+    pub fn cell_link(&self, row: usize, col: usize) -> Option<LinkTarget> {
+        self.description.cell_link(self.first_row + row, col)
+    }
+
+    pub fn cell_field(&self, row: usize, col: usize) -> Option<FormField> {
+        self.description.cell_field(self.first_row + row, col)
+    }
+
     pub fn render_cell_contents(
         &self,
         row: usize,
@@ -59,15 +114,20 @@ where
         instructions: &mut Instructions,
     ) {
         self.description
-            .render_cell_contents(row, col, rect, instructions);
+            .render_cell_contents(self.first_row + row, col, rect, instructions);
     }
-}
 
-impl<D> From<D> for RenderParams<D>
-where
-    D: GridDescription,
-{
-    fn from(description: D) -> Self {
+    pub fn cell_grid(
+        &self,
+        row: usize,
+        col: usize,
+        rect: &WRect,
+    ) -> Option<Box<dyn GridDescription + '_>> {
+        self.description.cell_grid(self.first_row + row, col, rect)
+    }
+
+    // Lays out the description in `grid_bounds`.
+    pub fn new(description: Box<D>, grid_bounds: WRect) -> Self {
         if description.row_height().is_none() && description.num_rows().is_none() {
             panic!("either row height or num rows must be set");
         }
@@ -75,37 +135,54 @@ where
             panic!("either col width or num cols must be set");
         }
 
-        let grid_bounds = description.bounds();
-
         let has_row_labels = description.row_label_width().is_some();
         let has_col_labels = description.col_label_height().is_some();
         let row_label_width = description.row_label_width().unwrap_or_else(Unit::zero);
         let col_label_height = description.col_label_height().unwrap_or_else(Unit::zero);
 
+        let has_right_row_labels = description.right_row_label_width().is_some();
+        let has_bottom_col_labels = description.bottom_col_label_height().is_some();
+        let right_row_label_width = description
+            .right_row_label_width()
+            .unwrap_or_else(Unit::zero);
+        let bottom_col_label_height = description
+            .bottom_col_label_height()
+            .unwrap_or_else(Unit::zero);
+
+        // Space left for the cells once the labels on all four sides are accounted for.
+        let cells_height = grid_bounds.height() - col_label_height - bottom_col_label_height;
+        let cells_width = grid_bounds.width() - row_label_width - right_row_label_width;
+
         let num_rows = description.num_rows().unwrap_or_else(|| {
             // unwrap: we check that both num_rows and row_height cannot be none.
             // If num_rows isn't set, we compute it from the label size, bounds, and cell size.
-            ((grid_bounds.height() - col_label_height) / description.row_height().unwrap()).trunc()
-                as usize
+            (cells_height / description.row_height().unwrap()).trunc() as usize
         });
         let num_cols = description.num_cols().unwrap_or_else(|| {
             // unwrap: we check that both num_cols and col_width cannot be none.
             // If num_cols isn't set, we compute it from the label size, bounds, and cell size.
-            ((grid_bounds.width() - row_label_width) / description.col_width().unwrap()).trunc()
-                as usize
+            (cells_width / description.col_width().unwrap()).trunc() as usize
         });
 
         let row_height = description.row_height().unwrap_or_else(|| {
             // unwrap: we check that both num_rows and row_height cannot be none.
             // If row_height isn't set, we compute is from the label size, bounds, and num rows.
-            (grid_bounds.height() - col_label_height) / description.num_rows().unwrap() as f64
+            // When paginating, the rows only need to fill one page.
+            let page_rows = description
+                .rows_per_page()
+                .map_or(num_rows, |rows| rows.min(num_rows));
+            cells_height / page_rows as f64
         });
         let col_width = description.col_width().unwrap_or_else(|| {
             // unwrap: we check that both num_cols and col_width cannot be none.
             // If col_width isn't set, we compute is from the label size, bounds, and num cols.
-            (grid_bounds.width() - row_label_width) / description.num_cols().unwrap() as f64
+            cells_width / description.num_cols().unwrap() as f64
         });
 
+        let col_label_orientation = description.col_label_orientation();
+        let rows_per_page = description.rows_per_page();
+        let repeat_col_labels = description.repeat_col_labels();
+        let repeat_row_labels = description.repeat_row_labels();
         let font = description.font();
 
         RenderParams {
@@ -115,10 +192,20 @@ where
             col_width,
             num_rows,
             num_cols,
+            first_row: 0,
+            total_rows: num_rows,
+            rows_per_page,
+            repeat_col_labels,
+            repeat_row_labels,
             has_row_labels,
             row_label_width,
+            has_right_row_labels,
+            right_row_label_width,
             has_col_labels,
             col_label_height,
+            has_bottom_col_labels,
+            bottom_col_label_height,
+            col_label_orientation,
             font,
         }
     }
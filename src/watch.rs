@@ -0,0 +1,53 @@
+use crate::Result;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+// How often to look at the files. Polling is plenty fast for files that people edit by hand.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    path.metadata()
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn report(result: Result<()>) {
+    match result {
+        Ok(()) => eprintln!("Done. Watching for changes; press Ctrl-C to stop."),
+        Err(err) => eprintln!("Error: {}", err),
+    }
+}
+
+/// Calls `generate` once, and again every time one of `paths` changes, until the program is
+/// stopped.
+///
+/// Errors from `generate` are printed instead of returned, so that a mistake in a file that is
+/// being edited doesn't stop the watch. A file that is deleted and written again, as some editors
+/// do when they save, counts as one change.
+pub fn watch_files<P, F>(paths: &[P], mut generate: F) -> !
+where
+    P: AsRef<Path>,
+    F: FnMut() -> Result<()>,
+{
+    let mut last_modified: Vec<Option<SystemTime>> =
+        paths.iter().map(|path| modified(path.as_ref())).collect();
+    report(generate());
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let now_modified: Vec<Option<SystemTime>> =
+            paths.iter().map(|path| modified(path.as_ref())).collect();
+        // Wait for a deleted file to come back, rather than generating without it.
+        if now_modified == last_modified || now_modified.iter().any(Option::is_none) {
+            continue;
+        }
+
+        for (path, (before, now)) in paths.iter().zip(last_modified.iter().zip(&now_modified)) {
+            if before != now {
+                eprintln!("{} changed.", path.as_ref().display());
+            }
+        }
+        last_modified = now_modified;
+        report(generate());
+    }
+}